@@ -1,25 +1,22 @@
 use super::algorithm_utilities::*;
 use super::round_key::RoundKey;
-use std::convert::TryInto;
 
 pub fn decrypt(bytes: u128, key: u128) -> u128 {
     decrypt_block(bytes, RoundKey::new(key, 10))
 }
 
 fn decrypt_block(bytes: u128, round_key: RoundKey) -> u128 {
-    let initial_state = inverse_sub_bytes(inverse_shift_rows(add_round_key(
-        bytes,
-        round_key.get(10).try_into().unwrap(),
-    )));
+    let initial_state =
+        inverse_sub_bytes(inverse_shift_rows(add_round_key(bytes, &round_key.get(10))));
 
     let state = (1..=9).rev().fold(initial_state, |state, round| {
         inverse_sub_bytes(inverse_shift_rows(inverse_mix_columns(add_round_key(
             state,
-            round_key.get(round).try_into().unwrap(),
+            &round_key.get(round),
         ))))
     });
 
-    add_round_key(state, round_key.get(0).try_into().unwrap())
+    add_round_key(state, &round_key.get(0))
 }
 
 #[cfg(test)]
@@ -1,22 +1,22 @@
 use super::algorithm_utilities::*;
 use super::round_key::RoundKey;
-use std::convert::TryInto;
+
+pub fn encrypt(bytes: u128, key: u128) -> u128 {
+    encrypt_block(bytes, RoundKey::new(key, 10))
+}
 
 pub fn encrypt_block(bytes: u128, round_key: RoundKey) -> u128 {
     let init_keys = round_key.get(0);
 
-    let initial_state = add_round_key(bytes, init_keys.try_into().unwrap());
+    let initial_state = add_round_key(bytes, &init_keys);
     let state = (1..=9).fold(initial_state, |state, round| {
         add_round_key(
             mix_columns(shift_rows(sub_bytes(state))),
-            round_key.get(round).try_into().unwrap(),
+            &round_key.get(round),
         )
     });
 
-    add_round_key(
-        shift_rows(sub_bytes(state)),
-        round_key.get(10).try_into().unwrap(),
-    )
+    add_round_key(shift_rows(sub_bytes(state)), &round_key.get(10))
 }
 
 #[cfg(test)]
@@ -32,4 +32,13 @@ mod tests {
         let expected = 0x370da167_1a3de46b_a6307c65_fbb14597;
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn encrypt_should_match_fips_197_example() {
+        let actual = encrypt(
+            0x00112233_44556677_8899aabb_ccddeeff,
+            0x00010203_04050607_08090a0b_0c0d0e0f,
+        );
+        assert_eq!(actual, 0x69c4e0d8_6a7b0430_d8cdb780_70b4c55a);
+    }
 }
@@ -2,5 +2,6 @@ mod algorithm_utilities;
 pub mod decrypt;
 pub mod encrypt;
 mod mds;
+pub mod modes;
 mod round_key;
 mod s_box;
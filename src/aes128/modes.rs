@@ -0,0 +1,235 @@
+use super::decrypt::decrypt;
+use super::encrypt::encrypt;
+use crate::error::{Error, Result};
+use crate::hexaa;
+use crate::padding;
+
+pub const BLOCK_SIZE: usize = 16;
+
+/// Encrypts the plaintext, PKCS#7 padded, in electronic codebook (ECB) mode:
+/// every block is encrypted independently with the key.
+pub fn ecb_encrypt(plaintext: &[u8], key: u128) -> Vec<u8> {
+    padding::pkcs7_pad(plaintext, BLOCK_SIZE)
+        .chunks_exact(BLOCK_SIZE)
+        .flat_map(|block| hexaa::u128_to_bytes(encrypt(hexaa::bytes_to_u128(block), key)))
+        .collect()
+}
+
+/// Decrypts an ECB mode ciphertext, and strips its PKCS#7 padding.
+/// Fails if the ciphertext is not a whole number of blocks, or is not validly padded.
+pub fn ecb_decrypt(ciphertext: &[u8], key: u128) -> Result<Vec<u8>> {
+    check_whole_blocks(ciphertext)?;
+    let padded: Vec<u8> = ciphertext
+        .chunks_exact(BLOCK_SIZE)
+        .flat_map(|block| hexaa::u128_to_bytes(decrypt(hexaa::bytes_to_u128(block), key)))
+        .collect();
+    padding::pkcs7_unpad(&padded, BLOCK_SIZE)
+}
+
+/// Encrypts the plaintext, PKCS#7 padded, in cipher block chaining (CBC) mode:
+/// every block is XOR'ed with the previous ciphertext block (the iv for the first block)
+/// before being encrypted.
+pub fn cbc_encrypt(plaintext: &[u8], key: u128, iv: u128) -> Vec<u8> {
+    let mut previous = iv;
+    padding::pkcs7_pad(plaintext, BLOCK_SIZE)
+        .chunks_exact(BLOCK_SIZE)
+        .flat_map(|block| {
+            previous = encrypt(hexaa::bytes_to_u128(block) ^ previous, key);
+            hexaa::u128_to_bytes(previous)
+        })
+        .collect()
+}
+
+/// Decrypts a CBC mode ciphertext, and strips its PKCS#7 padding.
+/// Fails if the ciphertext is not a whole number of blocks, or is not validly padded.
+pub fn cbc_decrypt(ciphertext: &[u8], key: u128, iv: u128) -> Result<Vec<u8>> {
+    check_whole_blocks(ciphertext)?;
+    let mut previous = iv;
+    let padded: Vec<u8> = ciphertext
+        .chunks_exact(BLOCK_SIZE)
+        .flat_map(|block| {
+            let block = hexaa::bytes_to_u128(block);
+            let plain = decrypt(block, key) ^ previous;
+            previous = block;
+            hexaa::u128_to_bytes(plain)
+        })
+        .collect();
+    padding::pkcs7_unpad(&padded, BLOCK_SIZE)
+}
+
+/// Encrypts, or decrypts, the data in counter (CTR) mode:
+/// the data is XOR'ed with the keystream of encrypted counter blocks.
+/// A counter block is the nonce followed by the block count,
+/// both as 64 bit little endian integers.
+pub fn ctr(data: &[u8], key: u128, nonce: u64) -> Vec<u8> {
+    let mut output = data.to_vec();
+    ctr_in_place(&mut output, key, nonce);
+    output
+}
+
+/// Encrypts, or decrypts, the data in place in CTR mode.
+pub fn ctr_in_place(data: &mut [u8], key: u128, nonce: u64) {
+    data.chunks_mut(BLOCK_SIZE)
+        .zip(0..)
+        .for_each(|(chunk, count)| {
+            let keystream = encrypt(ctr_block(nonce, count), key).to_be_bytes();
+            hexaa::xor_bytes_in_place(chunk, keystream);
+        });
+}
+
+/// The first length bytes of the CTR mode keystream for the key and nonce.
+pub fn ctr_keystream(key: u128, nonce: u64, length: usize) -> Vec<u8> {
+    ctr_keystream_at(key, nonce, 0, length)
+}
+
+/// Bytes offset to offset + length of the CTR mode keystream,
+/// encrypting only the counter blocks they fall in.
+pub fn ctr_keystream_at(key: u128, nonce: u64, offset: usize, length: usize) -> Vec<u8> {
+    let first = offset / BLOCK_SIZE;
+    let last = (offset + length).div_ceil(BLOCK_SIZE);
+    (first as u64..last as u64)
+        .flat_map(|count| hexaa::u128_to_bytes(encrypt(ctr_block(nonce, count), key)))
+        .skip(offset % BLOCK_SIZE)
+        .take(length)
+        .collect()
+}
+
+/// Replaces the plaintext of a CTR mode ciphertext at offset with new plaintext,
+/// re-encrypting only the bytes which change, as a seekable encrypted disk would.
+/// The ciphertext grows if the new plaintext runs past its end.
+/// Fails if the offset is past the end of the ciphertext.
+pub fn ctr_edit(
+    ciphertext: &[u8],
+    key: u128,
+    nonce: u64,
+    offset: usize,
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    if offset > ciphertext.len() {
+        return Err(Error::LengthMismatch {
+            expected: ciphertext.len(),
+            actual: offset,
+        });
+    }
+    let mut edited = ciphertext[..offset].to_vec();
+    edited.extend(hexaa::xor_bytes(
+        plaintext,
+        ctr_keystream_at(key, nonce, offset, plaintext.len()),
+    ));
+    if offset + plaintext.len() < ciphertext.len() {
+        edited.extend_from_slice(&ciphertext[offset + plaintext.len()..]);
+    }
+    Ok(edited)
+}
+
+fn check_whole_blocks(bytes: &[u8]) -> Result<()> {
+    if bytes.len().is_multiple_of(BLOCK_SIZE) {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch {
+            expected: bytes.len().next_multiple_of(BLOCK_SIZE),
+            actual: bytes.len(),
+        })
+    }
+}
+
+fn ctr_block(nonce: u64, count: u64) -> u128 {
+    let mut block = nonce.to_le_bytes().to_vec();
+    block.extend_from_slice(&count.to_le_bytes());
+    hexaa::bytes_to_u128(&block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+
+    const KEY: u128 = 0x2b7e1516_28aed2a6_abf71588_09cf4f3c;
+
+    #[test]
+    fn ecb_encrypt_should_match_sp800_38a_vector() {
+        let plaintext = encodings::hex_as_bytes("6bc1bee22e409f96e93d7e117393172a").unwrap();
+        let encrypted = ecb_encrypt(&plaintext, KEY);
+        assert_eq!(
+            encodings::bytes_to_hex(&encrypted[..16]),
+            "3ad77bb40d7a3660a89ecaf32466ef97"
+        );
+    }
+
+    #[test]
+    fn cbc_encrypt_should_match_sp800_38a_vector() {
+        let plaintext = encodings::hex_as_bytes(
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51",
+        )
+        .unwrap();
+        let encrypted = cbc_encrypt(&plaintext, KEY, 0x00010203_04050607_08090a0b_0c0d0e0f);
+        assert_eq!(
+            encodings::bytes_to_hex(&encrypted[..32]),
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2"
+        );
+    }
+
+    #[test]
+    fn ecb_and_cbc_should_round_trip() {
+        let plaintext = b"YELLOW SUBMARINE and some more bytes".to_vec();
+        let encrypted = ecb_encrypt(&plaintext, KEY);
+        assert_eq!(encrypted.len(), 48);
+        assert_eq!(ecb_decrypt(&encrypted, KEY), Ok(plaintext.clone()));
+        let encrypted = cbc_encrypt(&plaintext, KEY, 7);
+        assert_eq!(cbc_decrypt(&encrypted, KEY, 7), Ok(plaintext));
+    }
+
+    #[test]
+    fn decrypt_should_fail_for_partial_blocks_and_bad_padding() {
+        let encrypted = cbc_encrypt(b"some plaintext", KEY, 7);
+        assert_eq!(
+            cbc_decrypt(&encrypted[..15], KEY, 7),
+            Err(Error::LengthMismatch {
+                expected: 16,
+                actual: 15
+            })
+        );
+        assert_eq!(cbc_decrypt(&encrypted, KEY, 8), Err(Error::Padding));
+    }
+
+    #[test]
+    fn ctr_should_decrypt_cryptopals_challenge_18() {
+        let encrypted = encodings::b64_as_bytes(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        )
+        .unwrap();
+        let key = hexaa::bytes_to_u128(b"YELLOW SUBMARINE");
+        let decrypted = ctr(&encrypted, key, 0);
+        assert_eq!(
+            String::from_utf8(decrypted).unwrap(),
+            "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+        );
+    }
+
+    #[test]
+    fn ctr_edit_should_change_only_the_edited_bytes() {
+        let plaintext = b"the quick brown fox jumps over the lazy dog";
+        let encrypted = ctr(plaintext, KEY, 3);
+        let edited = ctr_edit(&encrypted, KEY, 3, 17, b"cat!").unwrap();
+        assert_eq!(
+            ctr(&edited, KEY, 3),
+            b"the quick brown fcat!umps over the lazy dog"
+        );
+        assert_eq!(edited[..17], encrypted[..17]);
+        assert_eq!(edited[21..], encrypted[21..]);
+
+        let extended = ctr_edit(&encrypted, KEY, 3, 40, b"dogs").unwrap();
+        assert_eq!(
+            ctr(&extended, KEY, 3)[..],
+            b"the quick brown fox jumps over the lazy dogs"[..]
+        );
+        assert!(ctr_edit(&encrypted, KEY, 3, 44, b"x").is_err());
+    }
+
+    #[test]
+    fn ctr_keystream_at_should_slice_the_keystream() {
+        let keystream = ctr_keystream(KEY, 9, 70);
+        assert_eq!(ctr_keystream_at(KEY, 9, 13, 40), keystream[13..53].to_vec());
+        assert_eq!(ctr_keystream_at(KEY, 9, 32, 0), vec![]);
+    }
+}
@@ -11,12 +11,11 @@ impl RoundKey {
         let mut round_keys = Vec::with_capacity(44);
         round_keys.extend_from_slice(&prev);
         (0..round_count).for_each(|round| {
-            prev[0] = prev[0]
-                ^ sbox.byte_wise_s_word_32(Self::left_cyclic_byte_rotate(prev[3]))
+            prev[0] ^= sbox.byte_wise_s_word_32(Self::left_cyclic_byte_rotate(prev[3]))
                 ^ Self::r_con(round);
-            prev[1] = prev[1] ^ prev[0];
-            prev[2] = prev[2] ^ prev[1];
-            prev[3] = prev[3] ^ prev[2];
+            prev[1] ^= prev[0];
+            prev[2] ^= prev[1];
+            prev[3] ^= prev[2];
 
             round_keys.push(prev[0]);
             round_keys.push(prev[1]);
@@ -26,9 +25,14 @@ impl RoundKey {
         RoundKey { round_keys }
     }
 
-    pub fn get(&self, round: usize) -> &[u32] {
+    pub fn get(&self, round: usize) -> [u32; 4] {
         let index = round * 4;
-        &self.round_keys[index..index + 4]
+        [
+            self.round_keys[index],
+            self.round_keys[index + 1],
+            self.round_keys[index + 2],
+            self.round_keys[index + 3],
+        ]
     }
 
     fn split_words(bits: u128) -> [u32; 4] {
@@ -0,0 +1,314 @@
+use cryptopals::aes128::modes;
+use cryptopals::{block_analysis, decrypt, encodings, encrypt, hexaa};
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+const USAGE: &str = "Usage: krypt [OPTIONS] <COMMAND> [ARGS]
+
+Commands:
+  encode <hex|base64>             Encodes the (raw) input
+  decode <hex|base64>             Decodes the input into raw bytes
+  xor <KEY>                       XORs the input with the repeated key
+  aes <encrypt|decrypt> <KEY>     AES-128 encrypts or decrypts the input
+  crack single-byte               Breaks single-byte XOR; every input line is a candidate
+  crack repeating-xor             Breaks repeating-key XOR
+  detect ecb                      Finds the input line most likely to be ECB encrypted
+
+Options:
+  -i, --input <FILE>              Reads the input from FILE instead of stdin
+  -o, --output <FILE>             Writes the output to FILE instead of stdout
+  --input-encoding <ENCODING>     Encoding of the input: raw (default), hex or base64
+  --output-encoding <ENCODING>    Encoding of the output: raw (default), hex or base64
+  --key-encoding <ENCODING>       Encoding of keys and IVs: raw (default), hex or base64
+  --mode <MODE>                   AES mode: ecb (default), cbc or ctr
+  --iv <IV>                       CBC initialization vector (default all zeroes)
+  --nonce <NONCE>                 CTR nonce, as a decimal number (default 0)
+  -h, --help                      Prints this help";
+
+fn main() {
+    env_logger::init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err(message) = parse(&args).and_then(|options| run(&options)) {
+        eprintln!("krypt: {}\n\n{}", message, USAGE);
+        process::exit(1);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Encoding {
+    Raw,
+    Hex,
+    Base64,
+}
+
+impl Encoding {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "raw" => Ok(Encoding::Raw),
+            "hex" => Ok(Encoding::Hex),
+            "base64" | "b64" => Ok(Encoding::Base64),
+            _ => Err(format!("unknown encoding `{}`", s)),
+        }
+    }
+
+    /// Decodes the text, ignoring whitespace (e.g. line breaks in base64 files).
+    fn decode(self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        if self == Encoding::Raw {
+            return Ok(bytes.to_vec());
+        }
+        let text: String = String::from_utf8_lossy(bytes)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let decoded = match self {
+            Encoding::Hex => encodings::hex_as_bytes(&text.to_ascii_lowercase()),
+            _ => encodings::b64_as_bytes(&text),
+        };
+        decoded.map_err(|e| e.to_string())
+    }
+
+    fn encode(self, bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            Encoding::Raw => bytes,
+            Encoding::Hex => (encodings::bytes_to_hex(&bytes) + "\n").into_bytes(),
+            Encoding::Base64 => (encodings::bytes_to_b64(bytes) + "\n").into_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Vec<String>,
+    input: Option<String>,
+    output: Option<String>,
+    input_encoding: Encoding,
+    output_encoding: Encoding,
+    key_encoding: Encoding,
+    mode: String,
+    iv: Option<String>,
+    nonce: u64,
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: vec![],
+        input: None,
+        output: None,
+        input_encoding: Encoding::Raw,
+        output_encoding: Encoding::Raw,
+        key_encoding: Encoding::Raw,
+        mode: "ecb".to_owned(),
+        iv: None,
+        nonce: 0,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or(format!("missing value for `{}`", arg))
+        };
+        match arg.as_str() {
+            "-i" | "--input" => options.input = Some(value()?),
+            "-o" | "--output" => options.output = Some(value()?),
+            "--input-encoding" => options.input_encoding = Encoding::parse(&value()?)?,
+            "--output-encoding" => options.output_encoding = Encoding::parse(&value()?)?,
+            "--key-encoding" => options.key_encoding = Encoding::parse(&value()?)?,
+            "--mode" => options.mode = value()?,
+            "--iv" => options.iv = Some(value()?),
+            "--nonce" => {
+                options.nonce = value()?
+                    .parse()
+                    .map_err(|_| "nonce should be a decimal number".to_owned())?
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option `{}`", flag))
+            }
+            positional => options.command.push(positional.to_owned()),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let command: Vec<&str> = options.command.iter().map(|s| s.as_str()).collect();
+    let input = read_input(options)?;
+    let output = match command.as_slice() {
+        ["encode", encoding] => Encoding::parse(encoding)?.encode(input),
+        ["decode", encoding] => Encoding::parse(encoding)?.decode(&input)?,
+        ["xor", key] => {
+            let key = options.key_encoding.decode(key.as_bytes())?;
+            let output = encrypt::repeated_key_xor(&options.input_encoding.decode(&input)?, &key)
+                .map_err(|e| e.to_string())?;
+            options.output_encoding.encode(output)
+        }
+        ["aes", direction, key] => {
+            let output = aes(
+                options,
+                direction,
+                key,
+                &options.input_encoding.decode(&input)?,
+            )?;
+            options.output_encoding.encode(output)
+        }
+        ["crack", "single-byte"] => {
+            let candidates = lines(&input)
+                .map(|line| options.input_encoding.decode(line))
+                .collect::<Result<Vec<Vec<u8>>, String>>()?;
+            let scorer = cryptopals::scoring::LetterFrequency::new();
+            let (key, plaintext, _) = candidates
+                .iter()
+                .map(|candidate| decrypt::single_byte_xor(candidate, &scorer))
+                .max_by(|a, b| a.2.total_cmp(&b.2))
+                .ok_or("no input to crack")?;
+            eprintln!("key: {}", encodings::bytes_to_hex(&[key]));
+            options.output_encoding.encode(plaintext)
+        }
+        ["crack", "repeating-xor"] => {
+            let ciphertext = options.input_encoding.decode(&input)?;
+            let key = decrypt::find_vignere_key(&ciphertext).map_err(|e| e.to_string())?;
+            eprintln!("key: {}", encodings::bytes_to_hex(&key));
+            let plaintext =
+                encrypt::repeated_key_xor(&ciphertext, &key).map_err(|e| e.to_string())?;
+            options.output_encoding.encode(plaintext)
+        }
+        ["detect", "ecb"] => {
+            let (index, line, classification) = lines(&input)
+                .enumerate()
+                .map(|(index, line)| {
+                    let ciphertext = options.input_encoding.decode(line)?;
+                    let classification = block_analysis::classify(&[ciphertext], modes::BLOCK_SIZE);
+                    Ok((index, line, classification))
+                })
+                .collect::<Result<Vec<_>, String>>()?
+                .into_iter()
+                .max_by_key(|(_, _, classification)| classification.report.repeated_blocks())
+                .ok_or("no input to examine")?;
+            let mut report = format!(
+                "line {}, {} repeated blocks, {:?} with confidence {:.3}: ",
+                index + 1,
+                classification.report.repeated_blocks(),
+                classification.mode,
+                classification.confidence
+            )
+            .into_bytes();
+            report.extend_from_slice(line);
+            report.push(b'\n');
+            let repetitions = block_analysis::describe(&classification.report);
+            if !repetitions.is_empty() {
+                report.extend_from_slice(repetitions.as_bytes());
+                report.push(b'\n');
+            }
+            report
+        }
+        [] => return Err("missing command".to_owned()),
+        _ => return Err(format!("unknown command `{}`", command.join(" "))),
+    };
+    write_output(options, &output)
+}
+
+fn aes(options: &Options, direction: &str, key: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+    let key = block(&options.key_encoding.decode(key.as_bytes())?, "key")?;
+    let iv = match &options.iv {
+        Some(iv) => block(&options.key_encoding.decode(iv.as_bytes())?, "iv")?,
+        None => 0,
+    };
+    match (options.mode.as_str(), direction) {
+        ("ecb", "encrypt") => Ok(modes::ecb_encrypt(input, key)),
+        ("ecb", "decrypt") => modes::ecb_decrypt(input, key).map_err(|e| e.to_string()),
+        ("cbc", "encrypt") => Ok(modes::cbc_encrypt(input, key, iv)),
+        ("cbc", "decrypt") => modes::cbc_decrypt(input, key, iv).map_err(|e| e.to_string()),
+        ("ctr", "encrypt") | ("ctr", "decrypt") => Ok(modes::ctr(input, key, options.nonce)),
+        ("ecb", _) | ("cbc", _) | ("ctr", _) => Err(format!("unknown direction `{}`", direction)),
+        (mode, _) => Err(format!("unknown mode `{}`", mode)),
+    }
+}
+
+fn block(bytes: &[u8], name: &str) -> Result<u128, String> {
+    if bytes.len() == modes::BLOCK_SIZE {
+        Ok(hexaa::bytes_to_u128(bytes))
+    } else {
+        Err(format!(
+            "{} should be {} bytes long",
+            name,
+            modes::BLOCK_SIZE
+        ))
+    }
+}
+
+fn lines(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    input
+        .split(|b| *b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty())
+}
+
+fn read_input(options: &Options) -> Result<Vec<u8>, String> {
+    match &options.input {
+        Some(path) => fs::read(path).map_err(|e| format!("cannot read `{}`: {}", path, e)),
+        None => {
+            let mut input = vec![];
+            io::stdin()
+                .read_to_end(&mut input)
+                .map_err(|e| format!("cannot read stdin: {}", e))?;
+            Ok(input)
+        }
+    }
+}
+
+fn write_output(options: &Options, output: &[u8]) -> Result<(), String> {
+    match &options.output {
+        Some(path) => {
+            fs::write(path, output).map_err(|e| format!("cannot write `{}`: {}", path, e))
+        }
+        None => io::stdout()
+            .write_all(output)
+            .map_err(|e| format!("cannot write stdout: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|s| s.to_owned()).collect()
+    }
+
+    #[test]
+    fn parse_should_read_options_anywhere() {
+        let options = parse(&args(
+            "aes --mode cbc decrypt -i in.txt KEY --input-encoding base64",
+        ))
+        .unwrap();
+        assert_eq!(options.command, vec!["aes", "decrypt", "KEY"]);
+        assert_eq!(options.mode, "cbc");
+        assert_eq!(options.input, Some("in.txt".to_owned()));
+        assert_eq!(options.input_encoding, Encoding::Base64);
+        assert_eq!(options.output_encoding, Encoding::Raw);
+    }
+
+    #[test]
+    fn parse_should_reject_unknown_options_and_missing_values() {
+        assert!(parse(&args("xor KEY --colour")).is_err());
+        assert!(parse(&args("xor KEY --output-encoding")).is_err());
+        assert!(parse(&args("xor KEY --output-encoding rot13")).is_err());
+    }
+
+    #[test]
+    fn decode_should_ignore_line_breaks() {
+        assert_eq!(
+            Encoding::Base64.decode(b"oRAS\noRA=\n"),
+            Ok(vec![0xa1, 0x10, 0x12, 0xa1, 0x10])
+        );
+        assert_eq!(Encoding::Hex.decode(b"A1\r\n10"), Ok(vec![0xa1, 0x10]));
+        assert!(Encoding::Hex.decode(b"a1g0").is_err());
+    }
+}
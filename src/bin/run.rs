@@ -0,0 +1,55 @@
+use cryptopals::challenges::{self, Challenge};
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage: run <all|NUMBER>...";
+
+fn main() {
+    env_logger::init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+
+    let mut selected: Vec<Box<dyn Challenge>> = vec![];
+    for arg in args.iter() {
+        if arg == "all" {
+            selected.extend(challenges::all());
+            continue;
+        }
+        match arg.parse().ok().and_then(challenges::get) {
+            Some(challenge) => selected.push(challenge),
+            None => {
+                eprintln!("No such challenge: `{}`\n{}", arg, USAGE);
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut failures = 0;
+    for challenge in selected.iter() {
+        println!("== Challenge {}: {}", challenge.number(), challenge.title());
+        match challenges::run(challenge.as_ref()) {
+            Ok(outcome) => {
+                println!("{}", outcome.output);
+                if outcome.passed {
+                    println!("-- PASS");
+                } else {
+                    println!("-- FAIL: unexpected output");
+                    failures += 1;
+                }
+            }
+            Err(e) => {
+                println!("-- FAIL: cannot read inputs: {}", e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} of {} challenges failed", failures, selected.len());
+        process::exit(1);
+    }
+}
@@ -0,0 +1,215 @@
+//! Bit-flipping attacks on CBC and CTR mode ciphertexts (cryptopals challenges 16 and 26).
+//!
+//! Neither mode authenticates the ciphertext. In CTR mode flipping a bit of the ciphertext
+//! flips the same bit of the plaintext; in CBC mode it flips the same bit of the plaintext
+//! of the next block, and scrambles the plaintext of its own block.
+//! So whoever knows part of the plaintext can change it to anything, e.g. to inject
+//! the `;admin=true;` that the encrypting side carefully filtered out of its input.
+
+use crate::aes128::modes;
+use crate::block_analysis;
+use crate::error::{Error, Result};
+use crate::hexaa::{self, LengthPolicy};
+use crate::oracle::{Counting, DecryptionOracle, EncryptionOracle};
+use crate::random::Random;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CipherMode {
+    Cbc,
+    Ctr,
+}
+
+const PREFIX: &[u8] = b"comment1=cooking%20MCs;userdata=";
+const SUFFIX: &[u8] = b";comment2=%20like%20a%20pound%20of%20bacon";
+
+/// Encrypts user data, quoted, between a fixed prefix and suffix,
+/// and checks decrypted data for `admin=true`.
+pub struct UserDataService {
+    mode: CipherMode,
+    key: u128,
+    iv: u128,
+    nonce: u64,
+}
+
+impl UserDataService {
+    pub fn new(mode: CipherMode, random: &mut Random) -> Self {
+        UserDataService {
+            mode,
+            key: random.next_u128(),
+            iv: random.next_u128(),
+            nonce: random.next_u64(),
+        }
+    }
+
+    /// Whether the decrypted data has an `admin=true` field, fields being separated by `;`.
+    pub fn is_admin(&self, ciphertext: &[u8]) -> Result<bool> {
+        Ok(self
+            .decrypt(ciphertext)?
+            .split(|b| *b == b';')
+            .any(|field| field == b"admin=true"))
+    }
+}
+
+/// Quotes out the field separator `;` and the `=` of the user data.
+/// ```
+/// assert_eq!(cryptopals::bit_flipping::quote(b";admin=true"), b"%3Badmin%3Dtrue".to_vec());
+/// ```
+pub fn quote(data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .flat_map(|b| match b {
+            b';' => b"%3B".to_vec(),
+            b'=' => b"%3D".to_vec(),
+            _ => vec![*b],
+        })
+        .collect()
+}
+
+impl EncryptionOracle for UserDataService {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let data = [PREFIX, &quote(plaintext), SUFFIX].concat();
+        Ok(match self.mode {
+            CipherMode::Cbc => modes::cbc_encrypt(&data, self.key, self.iv),
+            CipherMode::Ctr => modes::ctr(&data, self.key, self.nonce),
+        })
+    }
+}
+
+impl DecryptionOracle for UserDataService {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        match self.mode {
+            CipherMode::Cbc => modes::cbc_decrypt(ciphertext, self.key, self.iv),
+            CipherMode::Ctr => Ok(modes::ctr(ciphertext, self.key, self.nonce)),
+        }
+    }
+}
+
+/// A modified ciphertext which decrypts with the injected data in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Injection {
+    pub ciphertext: Vec<u8>,
+    /// Offset of the injected data in the plaintext.
+    pub offset: usize,
+    /// Number of queries made to the encryption and decryption oracles.
+    pub queries: u64,
+}
+
+/// Injects data into the plaintext of a ciphertext of the encryption oracle,
+/// by encrypting a placeholder of the same length and flipping it into the data,
+/// and checks with the decryption oracle that the data made it into the plaintext.
+/// The oracle should encrypt the same prefix before the input every time.
+/// In CBC mode the data can be at most a block long, since flipping the ciphertext
+/// of a block, to change the next, scrambles the plaintext of the block itself.
+pub fn inject<E, D>(
+    encryption: &E,
+    decryption: &D,
+    mode: CipherMode,
+    data: &[u8],
+) -> Result<Injection>
+where
+    E: EncryptionOracle,
+    D: DecryptionOracle,
+{
+    let encryption = Counting::new(encryption);
+    let decryption = Counting::new(decryption);
+    let (offset, ciphertext) = match mode {
+        CipherMode::Cbc => flip_cbc(&encryption, data)?,
+        CipherMode::Ctr => flip_ctr(&encryption, data)?,
+    };
+
+    let plaintext = decryption.decrypt(&ciphertext)?;
+    if plaintext.get(offset..offset + data.len()) != Some(data) {
+        return Err(Error::Cryptanalysis(
+            "injected data did not survive decryption".to_owned(),
+        ));
+    }
+    Ok(Injection {
+        ciphertext,
+        offset,
+        queries: encryption.queries() + decryption.queries(),
+    })
+}
+
+/// The plaintext which is flipped into the data.
+fn placeholder(length: usize) -> Vec<u8> {
+    vec![b'A'; length]
+}
+
+/// Encrypts two blocks of placeholder after the prefix, and flips the ciphertext
+/// of the first, sacrificed, block to flip the placeholder of the second into the data.
+fn flip_cbc<O: EncryptionOracle>(oracle: &O, data: &[u8]) -> Result<(usize, Vec<u8>)> {
+    let block_size = modes::BLOCK_SIZE;
+    if data.len() > block_size {
+        return Err(Error::LengthMismatch {
+            expected: block_size,
+            actual: data.len(),
+        });
+    }
+    let prefix_length = block_analysis::find_prefix_length(oracle, block_size)?;
+    let alignment = (block_size - prefix_length % block_size) % block_size;
+    let sacrificed = prefix_length + alignment;
+    let offset = sacrificed + block_size;
+
+    let mut ciphertext = oracle.encrypt(&placeholder(alignment + 2 * block_size))?;
+    flip(&mut ciphertext[sacrificed..sacrificed + data.len()], data)?;
+    Ok((offset, ciphertext))
+}
+
+/// Encrypts placeholder after the prefix, and flips its ciphertext into that of the data.
+fn flip_ctr<O: EncryptionOracle>(oracle: &O, data: &[u8]) -> Result<(usize, Vec<u8>)> {
+    let zero = oracle.encrypt(&[0])?;
+    let one = oracle.encrypt(&[1])?;
+    let offset = zero
+        .iter()
+        .zip(one.iter())
+        .position(|(a, b)| a != b)
+        .ok_or_else(|| Error::Cryptanalysis("input does not change the output".to_owned()))?;
+
+    let mut ciphertext = oracle.encrypt(&placeholder(data.len()))?;
+    flip(&mut ciphertext[offset..offset + data.len()], data)?;
+    Ok((offset, ciphertext))
+}
+
+/// Flips the ciphertext by the difference between the placeholder and the data.
+fn flip(ciphertext: &mut [u8], data: &[u8]) -> Result<()> {
+    let delta = hexaa::xor(data, placeholder(data.len()), LengthPolicy::Strict)?;
+    hexaa::xor_in_place(ciphertext, delta, LengthPolicy::Strict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_data_service_should_not_encrypt_admin_from_the_input() {
+        for mode in [CipherMode::Cbc, CipherMode::Ctr] {
+            let service = UserDataService::new(mode, &mut Random::with_seed(16));
+            let ciphertext = service.encrypt(b";admin=true;").unwrap();
+            assert!(!service.is_admin(&ciphertext).unwrap());
+        }
+    }
+
+    #[test]
+    fn inject_should_make_an_admin_in_either_mode() {
+        for mode in [CipherMode::Cbc, CipherMode::Ctr] {
+            let service = UserDataService::new(mode, &mut Random::with_seed(26));
+            let injection = inject(&service, &service, mode, b";admin=true;").unwrap();
+            assert!(service.is_admin(&injection.ciphertext).unwrap());
+            assert!(injection.queries > 0);
+        }
+    }
+
+    #[test]
+    fn inject_should_only_take_long_data_in_ctr_mode() {
+        let data = b";admin=true;role=superuser;x=";
+        let service = UserDataService::new(CipherMode::Cbc, &mut Random::with_seed(7));
+        assert!(inject(&service, &service, CipherMode::Cbc, data).is_err());
+
+        let service = UserDataService::new(CipherMode::Ctr, &mut Random::with_seed(7));
+        let injection = inject(&service, &service, CipherMode::Ctr, data).unwrap();
+        let plaintext = service.decrypt(&injection.ciphertext).unwrap();
+        assert_eq!(
+            &plaintext[injection.offset..injection.offset + data.len()],
+            data
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Telling ECB mode ciphertexts apart from those of chained modes (CBC).
+//!
+//! ECB encrypts equal plaintext blocks to equal ciphertext blocks,
+//! while the blocks of any other mode look random,
+//! and random blocks of 16 bytes practically never repeat.
+//! So repeated blocks in a ciphertext give ECB away,
+//! provided the plaintext repeats itself, which a chosen plaintext always can.
+
+use crate::aes128::modes;
+use crate::encodings;
+use crate::error::{Error, Result};
+use crate::oracle::EncryptionOracle;
+use crate::random::Random;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+}
+
+/// A block which appears more than once in the ciphertexts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Repetition {
+    pub block: Vec<u8>,
+    /// Positions of the block, as (ciphertext index, block index) pairs, in order.
+    pub positions: Vec<(usize, usize)>,
+}
+
+/// The blocks of a set of ciphertexts, for a block size, and which of them repeat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockReport {
+    pub block_size: usize,
+    /// Number of whole blocks in all the ciphertexts.
+    pub blocks: usize,
+    /// The repeated blocks, in the order of their first appearance.
+    pub repetitions: Vec<Repetition>,
+}
+
+impl BlockReport {
+    /// Number of blocks which repeat an earlier block.
+    pub fn repeated_blocks(&self) -> usize {
+        self.repetitions.iter().map(|r| r.positions.len() - 1).sum()
+    }
+}
+
+/// A block size, and how many blocks repeat when the ciphertexts are split into blocks of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockSizeCandidate {
+    pub block_size: usize,
+    pub repeated_blocks: usize,
+}
+
+/// A guess of the mode the ciphertexts were encrypted in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Classification {
+    pub mode: Mode,
+    /// Probability, from 0 to 1, that the mode is right.
+    pub confidence: f64,
+    pub report: BlockReport,
+}
+
+/// Splits every ciphertext into blocks, ignoring a trailing partial block,
+/// and finds the blocks which appear more than once, in any of the ciphertexts.
+/// ```
+/// use cryptopals::block_analysis;
+/// let report = block_analysis::analyze(&[b"abcdxxxxabcd"], 4);
+/// assert_eq!(report.blocks, 3);
+/// assert_eq!(report.repetitions[0].positions, vec![(0, 0), (0, 2)]);
+/// ```
+pub fn analyze<C: AsRef<[u8]>>(ciphertexts: &[C], block_size: usize) -> BlockReport {
+    let mut repetitions: Vec<Repetition> = vec![];
+    let mut first_seen: HashMap<&[u8], usize> = HashMap::new();
+    let mut blocks = 0;
+    for (index, ciphertext) in ciphertexts.iter().enumerate() {
+        for (position, block) in ciphertext.as_ref().chunks_exact(block_size).enumerate() {
+            blocks += 1;
+            match first_seen.get(block) {
+                Some(r) => repetitions[*r].positions.push((index, position)),
+                None => {
+                    first_seen.insert(block, repetitions.len());
+                    repetitions.push(Repetition {
+                        block: block.to_vec(),
+                        positions: vec![(index, position)],
+                    });
+                }
+            }
+        }
+    }
+    repetitions.retain(|r| r.positions.len() > 1);
+    BlockReport {
+        block_size,
+        blocks,
+        repetitions,
+    }
+}
+
+/// The block sizes, multiples of 8 up to 64, which divide the lengths of all the ciphertexts,
+/// with the most repeated bytes first, and the smallest block size first among equals.
+/// A ciphertext alone cannot tell apart a block size from its multiples and divisors,
+/// for any repetition of a block also repeats its halves.
+pub fn block_size_candidates<C: AsRef<[u8]>>(ciphertexts: &[C]) -> Vec<BlockSizeCandidate> {
+    let mut candidates: Vec<BlockSizeCandidate> = (8..=64)
+        .step_by(8)
+        .filter(|size| {
+            ciphertexts
+                .iter()
+                .all(|c| c.as_ref().len().is_multiple_of(*size))
+        })
+        .map(|block_size| BlockSizeCandidate {
+            block_size,
+            repeated_blocks: analyze(ciphertexts, block_size).repeated_blocks(),
+        })
+        .collect();
+    candidates.sort_by_key(|c| {
+        (
+            std::cmp::Reverse(c.repeated_blocks * c.block_size),
+            c.block_size,
+        )
+    });
+    candidates
+}
+
+/// Classifies ciphertexts of unknown plaintexts as ECB if any block repeats.
+/// The confidence of ECB is the chance that the repetitions are not a coincidence.
+/// No repetition is only weak evidence against ECB, as the plaintexts may not repeat,
+/// so CBC is reported with a confidence of 0.5; see `detect_mode` for a conclusive test.
+pub fn classify<C: AsRef<[u8]>>(ciphertexts: &[C], block_size: usize) -> Classification {
+    let report = analyze(ciphertexts, block_size);
+    if report.repeated_blocks() > 0 {
+        Classification {
+            mode: Mode::Ecb,
+            confidence: 1. - chance_of_repetition(report.blocks, block_size),
+            report,
+        }
+    } else {
+        Classification {
+            mode: Mode::Cbc,
+            confidence: 0.5,
+            report,
+        }
+    }
+}
+
+/// Detects the mode of an encryption oracle with a chosen plaintext
+/// of three equal blocks: however much the oracle prepends to it,
+/// ECB encrypts two aligned copies of the same plaintext block the same.
+/// Makes a single query.
+pub fn detect_mode<O: EncryptionOracle>(oracle: &O, block_size: usize) -> Result<Classification> {
+    let ciphertext = oracle.encrypt(&vec![0; 3 * block_size])?;
+    let mut classification = classify(&[ciphertext], block_size);
+    if classification.mode == Mode::Cbc {
+        classification.confidence = 1.;
+    }
+    Ok(classification)
+}
+
+/// Grows the input of a block cipher encryption oracle until the ciphertext grows by a block.
+/// Returns the block size, and the total length of what the oracle adds to the input.
+pub fn find_block_size<O: EncryptionOracle>(oracle: &O) -> Result<(usize, usize)> {
+    let initial = oracle.encrypt(&[])?.len();
+    for length in 1..=256 {
+        let grown = oracle.encrypt(&vec![0; length])?.len();
+        if grown > initial {
+            // the input filled the last block exactly, and padding took a whole block
+            return Ok((grown - initial, initial - length));
+        }
+    }
+    Err(Error::Cryptanalysis(
+        "ciphertext does not grow with the input".to_owned(),
+    ))
+}
+
+/// The nth block of the bytes, if they are long enough to hold it whole.
+/// ```
+/// use cryptopals::block_analysis::nth_block;
+/// assert_eq!(nth_block(b"aaaabbbbcc", 1, 4), Some(&b"bbbb"[..]));
+/// assert_eq!(nth_block(b"aaaabbbbcc", 2, 4), None);
+/// ```
+pub fn nth_block(bytes: &[u8], n: usize, block_size: usize) -> Option<&[u8]> {
+    bytes.get(n * block_size..(n + 1) * block_size)
+}
+
+/// Finds the length of the data a block cipher encryption oracle prepends to the input,
+/// which should be the same for every query, in a mode where blocks are encrypted
+/// independently of the following ones (e.g. ECB or CBC, but not CTR).
+/// The first block which changes with the input is the one the prefix ends in.
+/// Inputs ending in two different bytes change it, until the input is long enough
+/// to push those bytes out of it, into the next block.
+pub fn find_prefix_length<O: EncryptionOracle>(oracle: &O, block_size: usize) -> Result<usize> {
+    let zero = oracle.encrypt(&[0])?;
+    let one = oracle.encrypt(&[1])?;
+    let block = (0..zero.len() / block_size)
+        .find(|b| nth_block(&zero, *b, block_size) != nth_block(&one, *b, block_size))
+        .ok_or_else(|| Error::Cryptanalysis("input does not change the output".to_owned()))?;
+    for padding in 1..=block_size + 1 {
+        let mut input = vec![0; padding];
+        let zero = oracle.encrypt(&input)?;
+        input[padding - 1] = 1;
+        let one = oracle.encrypt(&input)?;
+        if nth_block(&zero, block, block_size) == nth_block(&one, block, block_size) {
+            // padding - 1 bytes of input fill the block
+            return Ok((block + 1) * block_size - (padding - 1));
+        }
+    }
+    Err(Error::Cryptanalysis(
+        "could not find the end of the prefix".to_owned(),
+    ))
+}
+
+/// The chance that any two of the given number of random blocks are equal (birthday bound).
+fn chance_of_repetition(blocks: usize, block_size: usize) -> f64 {
+    let pairs = (blocks * blocks.saturating_sub(1)) as f64 / 2.;
+    (pairs * 2f64.powi(-8 * block_size as i32)).min(1.)
+}
+
+/// The encryption oracle of cryptopals challenge 11: it encrypts the plaintext,
+/// between 5 to 10 random bytes before and after it, with a random key,
+/// in ECB or CBC (with a random IV) mode chosen at random.
+pub struct RandomModeOracle {
+    random: Mutex<Random>,
+    last_mode: Mutex<Option<Mode>>,
+}
+
+impl RandomModeOracle {
+    pub fn new(random: Random) -> Self {
+        RandomModeOracle {
+            random: Mutex::new(random),
+            last_mode: Mutex::new(None),
+        }
+    }
+
+    /// The mode of the last encryption, to check the detection against.
+    pub fn last_mode(&self) -> Option<Mode> {
+        *self.last_mode.lock().unwrap()
+    }
+}
+
+impl EncryptionOracle for RandomModeOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut random = self.random.lock().unwrap();
+        let (before, after) = (random.range(5, 11), random.range(5, 11));
+        let mut padded = random.bytes(before);
+        padded.extend_from_slice(plaintext);
+        padded.extend(random.bytes(after));
+
+        let key = random.next_u128();
+        let (mode, ciphertext) = if random.next_bool() {
+            (Mode::Ecb, modes::ecb_encrypt(&padded, key))
+        } else {
+            let iv = random.next_u128();
+            (Mode::Cbc, modes::cbc_encrypt(&padded, key, iv))
+        };
+        *self.last_mode.lock().unwrap() = Some(mode);
+        Ok(ciphertext)
+    }
+}
+
+/// Checks `detect_mode` against the random mode oracle, returning the number of the trials
+/// in which the mode was detected correctly.
+pub fn validate_detector(random: Random, trials: usize) -> Result<usize> {
+    let oracle = RandomModeOracle::new(random);
+    let mut detected = 0;
+    for _ in 0..trials {
+        if Some(detect_mode(&oracle, modes::BLOCK_SIZE)?.mode) == oracle.last_mode() {
+            detected += 1;
+        }
+    }
+    Ok(detected)
+}
+
+/// Renders the positions of the repeated blocks, e.g. for reporting on the command line.
+pub fn describe(report: &BlockReport) -> String {
+    report
+        .repetitions
+        .iter()
+        .map(|r| {
+            let positions: Vec<String> = r
+                .positions
+                .iter()
+                .map(|(index, position)| format!("{}:{}", index, position))
+                .collect();
+            format!(
+                "{} at {}",
+                encodings::bytes_to_hex(&r.block),
+                positions.join(", ")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexaa;
+
+    #[test]
+    fn analyze_should_find_repetitions_across_ciphertexts() {
+        let report = analyze(&[b"aaaabbbb".to_vec(), b"bbbbccccaa".to_vec()], 4);
+        assert_eq!(report.blocks, 4);
+        assert_eq!(report.repeated_blocks(), 1);
+        assert_eq!(report.repetitions[0].block, b"bbbb".to_vec());
+        assert_eq!(report.repetitions[0].positions, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn classify_should_tell_ecb_from_cbc() {
+        let plaintext = [b'A'; 64];
+        let key = hexaa::bytes_to_u128(b"YELLOW SUBMARINE");
+        let ecb = classify(&[modes::ecb_encrypt(&plaintext, key)], 16);
+        assert_eq!(ecb.mode, Mode::Ecb);
+        assert!(ecb.confidence > 0.999);
+        let cbc = classify(&[modes::cbc_encrypt(&plaintext, key, 1)], 16);
+        assert_eq!(cbc.mode, Mode::Cbc);
+    }
+
+    #[test]
+    fn block_size_candidates_should_include_the_block_size() {
+        let plaintext = [b'A'; 64];
+        let ciphertext = modes::ecb_encrypt(&plaintext, 42);
+        let candidates = block_size_candidates(&[ciphertext]);
+        assert!(candidates.iter().all(|c| c.block_size % 8 == 0));
+        assert!(candidates
+            .iter()
+            .any(|c| c.block_size == 16 && c.repeated_blocks == 3));
+    }
+
+    #[test]
+    fn detect_mode_should_always_be_right_against_the_random_oracle() {
+        assert_eq!(validate_detector(Random::with_seed(11), 40), Ok(40));
+    }
+}
@@ -0,0 +1,117 @@
+//! Recovering the key of CBC mode encryption which uses the key as the IV
+//! (cryptopals challenge 27).
+//!
+//! Decrypting C1, 0, C1 gives P1 = D(C1) ^ IV as the first block, and D(C1) ^ 0
+//! as the third, so the two XOR'ed together are the IV, which is the key.
+//! A receiver which reports the plaintexts it rejects, e.g. for not being ASCII,
+//! gives both away in answer to a single query.
+
+use crate::aes128::modes::{self, BLOCK_SIZE};
+use crate::error::{Error, Result};
+use crate::hexaa;
+use crate::oracle::{DecryptionOracle, EncryptionOracle};
+use crate::random::Random;
+
+/// Encrypts user data in CBC mode with the key as the IV,
+/// and rejects decrypted data which is not ASCII, reporting it in the error.
+pub struct KeyAsIvService {
+    key: u128,
+}
+
+impl KeyAsIvService {
+    pub fn new(random: &mut Random) -> Self {
+        KeyAsIvService {
+            key: random.next_u128(),
+        }
+    }
+
+    /// Whether the key is the one the service uses, to check the attack.
+    pub fn is_key(&self, key: u128) -> bool {
+        self.key == key
+    }
+}
+
+impl EncryptionOracle for KeyAsIvService {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        Ok(modes::cbc_encrypt(plaintext, self.key, self.key))
+    }
+}
+
+impl DecryptionOracle for KeyAsIvService {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let plaintext = modes::cbc_decrypt(ciphertext, self.key, self.key)?;
+        if plaintext.is_ascii() {
+            Ok(plaintext)
+        } else {
+            Err(Error::InvalidPlaintext(plaintext))
+        }
+    }
+}
+
+/// Recovers the key from a ciphertext of at least two blocks, with one query to
+/// a decryption oracle which leaks the plaintexts it rejects.
+/// The ciphertext is sent as C1, 0, C1 followed by the rest of it,
+/// so that it still ends with validly padded blocks.
+pub fn recover_key<O: DecryptionOracle>(oracle: &O, ciphertext: &[u8]) -> Result<u128> {
+    if ciphertext.len() < 2 * BLOCK_SIZE {
+        return Err(Error::LengthMismatch {
+            expected: 2 * BLOCK_SIZE,
+            actual: ciphertext.len(),
+        });
+    }
+    let first = &ciphertext[..BLOCK_SIZE];
+    let query = [first, &[0; BLOCK_SIZE], ciphertext].concat();
+    let plaintext = match oracle.decrypt(&query) {
+        Err(Error::InvalidPlaintext(plaintext)) => plaintext,
+        Err(e) => return Err(e),
+        Ok(_) => {
+            return Err(Error::Cryptanalysis(
+                "the oracle accepted the plaintext, which it did not leak".to_owned(),
+            ))
+        }
+    };
+    if plaintext.len() < 3 * BLOCK_SIZE {
+        return Err(Error::Cryptanalysis(
+            "the leaked plaintext is too short".to_owned(),
+        ));
+    }
+    Ok(hexaa::bytes_to_u128(&hexaa::xor_bytes(
+        &plaintext[..BLOCK_SIZE],
+        &plaintext[2 * BLOCK_SIZE..3 * BLOCK_SIZE],
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::Counting;
+
+    #[test]
+    fn recover_key_should_find_the_key_with_one_query() {
+        let service = KeyAsIvService::new(&mut Random::with_seed(27));
+        let ciphertext = service
+            .encrypt(b"comment1=cooking%20MCs;userdata=x;comment2=%20like%20a%20pound")
+            .unwrap();
+        let oracle = Counting::new(&service);
+        let key = recover_key(&oracle, &ciphertext).unwrap();
+        assert!(service.is_key(key));
+        assert_eq!(oracle.queries(), 1);
+        assert_eq!(
+            modes::cbc_decrypt(&ciphertext, key, key).unwrap()[..9],
+            b"comment1="[..]
+        );
+    }
+
+    #[test]
+    fn recover_key_should_fail_without_a_leak() {
+        let service = KeyAsIvService::new(&mut Random::with_seed(2));
+        let ciphertext = service.encrypt(&[b'a'; 32]).unwrap();
+        let quiet = crate::oracle::from_fn(|c: &[u8]| {
+            service
+                .decrypt(c)
+                .map_err(|_| Error::Decoding("not ASCII".to_owned()))
+        });
+        assert!(recover_key(&quiet, &ciphertext).is_err());
+        assert!(recover_key(&service, &ciphertext[..16]).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use super::Challenge;
+use crate::encodings;
+use crate::error::Result;
+
+pub struct Challenge1;
+
+impl Challenge for Challenge1 {
+    fn number(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Convert hex to base64"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        encodings::hex_to_b64(
+            "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d",
+        )
+    }
+
+    fn expected(&self) -> u64 {
+        0xe2b8_a477_5fb5_d732
+    }
+}
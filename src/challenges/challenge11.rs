@@ -0,0 +1,30 @@
+use super::Challenge;
+use crate::block_analysis;
+use crate::error::Result;
+use crate::random::Random;
+
+pub struct Challenge11;
+
+const TRIALS: usize = 50;
+
+impl Challenge for Challenge11 {
+    fn number(&self) -> u32 {
+        11
+    }
+
+    fn title(&self) -> &'static str {
+        "An ECB/CBC detection oracle"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let detected = block_analysis::validate_detector(Random::from_time(), TRIALS)?;
+        Ok(format!(
+            "detected the mode in {} of {} trials",
+            detected, TRIALS
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0x7bdc_8b58_5e5d_ef4e
+    }
+}
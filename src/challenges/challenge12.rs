@@ -0,0 +1,28 @@
+use super::{Challenge, SECRET_SUFFIX};
+use crate::ecb_byte_at_a_time::{self, SecretSuffixOracle};
+use crate::encodings;
+use crate::error::Result;
+use crate::random::Random;
+
+pub struct Challenge12;
+
+impl Challenge for Challenge12 {
+    fn number(&self) -> u32 {
+        12
+    }
+
+    fn title(&self) -> &'static str {
+        "Byte-at-a-time ECB decryption (Simple)"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let secret = encodings::b64_as_bytes(SECRET_SUFFIX)?;
+        let oracle = SecretSuffixOracle::new(&secret, &mut Random::from_time());
+        let recovery = ecb_byte_at_a_time::recover_secret(&oracle)?;
+        Ok(String::from_utf8_lossy(&recovery.secret).into_owned())
+    }
+
+    fn expected(&self) -> u64 {
+        0x6322_d39c_a221_15a5
+    }
+}
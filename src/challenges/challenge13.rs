@@ -0,0 +1,28 @@
+use super::Challenge;
+use crate::cookie;
+use crate::ecb_cut_and_paste::{self, ProfileService};
+use crate::error::Result;
+use crate::random::Random;
+
+pub struct Challenge13;
+
+impl Challenge for Challenge13 {
+    fn number(&self) -> u32 {
+        13
+    }
+
+    fn title(&self) -> &'static str {
+        "ECB cut-and-paste"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let service = ProfileService::new(&mut Random::from_time());
+        let forgery = ecb_cut_and_paste::forge_last_value(&service, b"user", b"admin")?;
+        let profile = service.decrypt_profile(&forgery.ciphertext)?;
+        Ok(cookie::encode(&profile))
+    }
+
+    fn expected(&self) -> u64 {
+        0xf03e_e759_c760_dd83
+    }
+}
@@ -0,0 +1,29 @@
+use super::Challenge;
+use crate::bit_flipping::{self, CipherMode, UserDataService};
+use crate::error::Result;
+use crate::random::Random;
+
+pub struct Challenge16;
+
+impl Challenge for Challenge16 {
+    fn number(&self) -> u32 {
+        16
+    }
+
+    fn title(&self) -> &'static str {
+        "CBC bitflipping attacks"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let service = UserDataService::new(CipherMode::Cbc, &mut Random::from_time());
+        let injection = bit_flipping::inject(&service, &service, CipherMode::Cbc, b";admin=true;")?;
+        Ok(format!(
+            "admin: {}",
+            service.is_admin(&injection.ciphertext)?
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0xb310_7801_cf04_31f2
+    }
+}
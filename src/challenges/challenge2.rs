@@ -0,0 +1,26 @@
+use super::Challenge;
+use crate::encodings;
+use crate::error::Result;
+
+pub struct Challenge2;
+
+impl Challenge for Challenge2 {
+    fn number(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Fixed XOR"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        encodings::xor_hexes(
+            "1c0111001f010100061a024b53535009181c",
+            "686974207468652062756c6c277320657965",
+        )
+    }
+
+    fn expected(&self) -> u64 {
+        0x7fae_c27f_2bf6_30a6
+    }
+}
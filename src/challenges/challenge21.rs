@@ -0,0 +1,27 @@
+use super::Challenge;
+use crate::error::Result;
+use crate::mt19937::Mt19937;
+
+pub struct Challenge21;
+
+impl Challenge for Challenge21 {
+    fn number(&self) -> u32 {
+        21
+    }
+
+    fn title(&self) -> &'static str {
+        "Implement the MT19937 Mersenne Twister RNG"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let mut mt = Mt19937::new(5489);
+        Ok((0..5)
+            .map(|_| mt.next_u32().to_string())
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
+    fn expected(&self) -> u64 {
+        0x6c4d_dd5d_4562_ebb8
+    }
+}
@@ -0,0 +1,37 @@
+use super::Challenge;
+use crate::error::Result;
+use crate::mt19937::{self, Mt19937};
+use crate::random::Random;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct Challenge22;
+
+impl Challenge for Challenge22 {
+    fn number(&self) -> u32 {
+        22
+    }
+
+    fn title(&self) -> &'static str {
+        "Crack an MT19937 seed"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        // simulates the wait of 40 to 1000 seconds before and after seeding
+        let mut random = Random::from_time();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as u32)
+            .unwrap_or(0);
+        let seed = now + random.range(40, 1001) as u32;
+        let now = seed + random.range(40, 1001) as u32;
+        let output = Mt19937::new(seed).next_u32();
+        Ok(format!(
+            "seed recovered: {}",
+            mt19937::recover_time_seed(&[output], now, 2000) == Some(seed)
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0x5057_4fd9_128d_367f
+    }
+}
@@ -0,0 +1,30 @@
+use super::Challenge;
+use crate::error::Result;
+use crate::mt19937::Mt19937;
+use crate::random::Random;
+
+pub struct Challenge23;
+
+impl Challenge for Challenge23 {
+    fn number(&self) -> u32 {
+        23
+    }
+
+    fn title(&self) -> &'static str {
+        "Clone an MT19937 RNG from its output"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let mut mt = Mt19937::new(Random::from_time().next_u64() as u32);
+        let outputs: Vec<u32> = (0..624).map(|_| mt.next_u32()).collect();
+        let mut clone = Mt19937::from_outputs(&outputs)?;
+        Ok(format!(
+            "clone predicts: {}",
+            (0..1000).all(|_| clone.next_u32() == mt.next_u32())
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0xce7d_b41c_fde5_b362
+    }
+}
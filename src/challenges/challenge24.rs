@@ -0,0 +1,33 @@
+use super::Challenge;
+use crate::error::Result;
+use crate::mt19937;
+use crate::random::Random;
+
+pub struct Challenge24;
+
+impl Challenge for Challenge24 {
+    fn number(&self) -> u32 {
+        24
+    }
+
+    fn title(&self) -> &'static str {
+        "Create the MT19937 stream cipher and break it"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let mut random = Random::from_time();
+        let key = random.next_u64() as u16;
+        let length = random.range(0, 20);
+        let mut plaintext = random.bytes(length);
+        plaintext.extend_from_slice(&[b'A'; 14]);
+        let ciphertext = mt19937::stream(&plaintext, key);
+        Ok(format!(
+            "key recovered: {}",
+            mt19937::recover_stream_key(&ciphertext, &[b'A'; 14])? == key
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0x5434_8d2d_2c9d_b7bf
+    }
+}
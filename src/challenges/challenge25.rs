@@ -0,0 +1,43 @@
+use super::Challenge;
+use crate::aes128::modes;
+use crate::encodings;
+use crate::error::Result;
+use crate::hexaa;
+use crate::random::Random;
+use crate::random_access_ctr::{self, EncryptedDisk};
+
+pub struct Challenge25;
+
+impl Challenge for Challenge25 {
+    fn number(&self) -> u32 {
+        25
+    }
+
+    fn title(&self) -> &'static str {
+        "Break \"random access read/write\" AES CTR"
+    }
+
+    /// The input of challenge 25 is that of challenge 7.
+    fn inputs(&self) -> &'static [&'static str] {
+        &["challenge7"]
+    }
+
+    fn solve(&self, inputs: &[String]) -> Result<String> {
+        let encrypted = inputs[0]
+            .lines()
+            .map(encodings::b64_as_bytes)
+            .collect::<Result<Vec<Vec<u8>>>>()?
+            .concat();
+        let key = hexaa::bytes_to_u128(b"YELLOW SUBMARINE");
+        let plaintext = modes::ecb_decrypt(&encrypted, key)?;
+
+        let disk = EncryptedDisk::new(&mut Random::from_time());
+        let ciphertext = disk.encrypt(&plaintext);
+        let recovery = random_access_ctr::recover_plaintext(&disk, &ciphertext, ciphertext.len())?;
+        Ok(String::from_utf8_lossy(&recovery.plaintext).into_owned())
+    }
+
+    fn expected(&self) -> u64 {
+        0x6755_9bda_eacc_9941
+    }
+}
@@ -0,0 +1,29 @@
+use super::Challenge;
+use crate::bit_flipping::{self, CipherMode, UserDataService};
+use crate::error::Result;
+use crate::random::Random;
+
+pub struct Challenge26;
+
+impl Challenge for Challenge26 {
+    fn number(&self) -> u32 {
+        26
+    }
+
+    fn title(&self) -> &'static str {
+        "CTR bitflipping"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let service = UserDataService::new(CipherMode::Ctr, &mut Random::from_time());
+        let injection = bit_flipping::inject(&service, &service, CipherMode::Ctr, b";admin=true;")?;
+        Ok(format!(
+            "admin: {}",
+            service.is_admin(&injection.ciphertext)?
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0xb310_7801_cf04_31f2
+    }
+}
@@ -0,0 +1,28 @@
+use super::Challenge;
+use crate::cbc_key_as_iv::{self, KeyAsIvService};
+use crate::error::Result;
+use crate::oracle::EncryptionOracle;
+use crate::random::Random;
+
+pub struct Challenge27;
+
+impl Challenge for Challenge27 {
+    fn number(&self) -> u32 {
+        27
+    }
+
+    fn title(&self) -> &'static str {
+        "Recover the key from CBC with IV=Key"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let service = KeyAsIvService::new(&mut Random::from_time());
+        let ciphertext = service.encrypt(b"comment1=cooking%20MCs;userdata=;comment2=%20like")?;
+        let key = cbc_key_as_iv::recover_key(&service, &ciphertext)?;
+        Ok(format!("key recovered: {}", service.is_key(key)))
+    }
+
+    fn expected(&self) -> u64 {
+        0x5434_8d2d_2c9d_b7bf
+    }
+}
@@ -0,0 +1,33 @@
+use super::Challenge;
+use crate::error::Result;
+use crate::length_extension::SecretPrefixMac;
+use crate::oracle::MacOracle;
+use crate::random::Random;
+use crate::sha1::Sha1;
+
+pub struct Challenge28;
+
+impl Challenge for Challenge28 {
+    fn number(&self) -> u32 {
+        28
+    }
+
+    fn title(&self) -> &'static str {
+        "Implement a SHA-1 keyed MAC"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let service = SecretPrefixMac::<Sha1>::new(16..=16, &mut Random::from_time());
+        let message = b"Let's get the party started";
+        let mac = service.mac(message);
+        Ok(format!(
+            "verifies: {}\ntampered message verifies: {}",
+            service.verify(message, &mac)?,
+            service.verify(b"Let's get the party startee", &mac)?
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0xc417_8b5f_e87d_af01
+    }
+}
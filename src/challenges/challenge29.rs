@@ -0,0 +1,43 @@
+use super::Challenge;
+use crate::error::Result;
+use crate::length_extension::{self, SecretPrefixMac};
+use crate::oracle::MacOracle;
+use crate::random::Random;
+use crate::sha1::Sha1;
+
+pub struct Challenge29;
+
+impl Challenge for Challenge29 {
+    fn number(&self) -> u32 {
+        29
+    }
+
+    fn title(&self) -> &'static str {
+        "Break a SHA-1 keyed MAC using length extension"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let service = SecretPrefixMac::<Sha1>::new(1..=64, &mut Random::from_time());
+        let message =
+            b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+        let forgery = length_extension::forge::<Sha1, _>(
+            &service,
+            message,
+            &service.mac(message),
+            b";admin=true",
+            1..=64,
+        )?;
+        Ok(format!(
+            "admin: {}",
+            service.verify(&forgery.message, &forgery.mac)?
+                && forgery
+                    .message
+                    .split(|b| *b == b';')
+                    .any(|field| field == b"admin=true")
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0xb310_7801_cf04_31f2
+    }
+}
@@ -0,0 +1,27 @@
+use super::Challenge;
+use crate::decrypt;
+use crate::error::Result;
+
+pub struct Challenge3;
+
+impl Challenge for Challenge3 {
+    fn number(&self) -> u32 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Single-byte XOR cipher"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &["challenge3"]
+    }
+
+    fn solve(&self, inputs: &[String]) -> Result<String> {
+        decrypt::single_char_xor(&[inputs[0].trim()]).map(|(_, plaintext)| plaintext)
+    }
+
+    fn expected(&self) -> u64 {
+        0x51db_fc20_30fc_9c6f
+    }
+}
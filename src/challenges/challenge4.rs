@@ -0,0 +1,28 @@
+use super::Challenge;
+use crate::decrypt;
+use crate::error::Result;
+
+pub struct Challenge4;
+
+impl Challenge for Challenge4 {
+    fn number(&self) -> u32 {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Detect single-character XOR"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &["challenge4"]
+    }
+
+    fn solve(&self, inputs: &[String]) -> Result<String> {
+        let lines: Vec<&str> = inputs[0].lines().filter(|l| !l.is_empty()).collect();
+        decrypt::single_char_xor(&lines).map(|(_, plaintext)| plaintext)
+    }
+
+    fn expected(&self) -> u64 {
+        0x0148_3f34_2c5a_e0a5
+    }
+}
@@ -0,0 +1,24 @@
+use super::Challenge;
+use crate::encrypt;
+use crate::error::Result;
+
+pub struct Challenge5;
+
+impl Challenge for Challenge5 {
+    fn number(&self) -> u32 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "Implement repeating-key XOR"
+    }
+
+    fn solve(&self, _: &[String]) -> Result<String> {
+        let text = "Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+        encrypt::repeated_key_xor_with_strings(text, "ICE")
+    }
+
+    fn expected(&self) -> u64 {
+        0xd5eb_7856_0392_55cc
+    }
+}
@@ -0,0 +1,40 @@
+use super::Challenge;
+use crate::decrypt;
+use crate::encodings;
+use crate::encrypt;
+use crate::error::Result;
+
+pub struct Challenge6;
+
+impl Challenge for Challenge6 {
+    fn number(&self) -> u32 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Break repeating-key XOR"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &["challenge6"]
+    }
+
+    fn solve(&self, inputs: &[String]) -> Result<String> {
+        let encrypted = inputs[0]
+            .lines()
+            .map(encodings::b64_as_bytes)
+            .collect::<Result<Vec<Vec<u8>>>>()?
+            .concat();
+        let key = decrypt::find_vignere_key(&encrypted)?;
+        let decrypted = encrypt::repeated_key_xor(&encrypted, &key)?;
+        Ok(format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&key),
+            String::from_utf8_lossy(&decrypted)
+        ))
+    }
+
+    fn expected(&self) -> u64 {
+        0xbce6_fd76_d36b_6013
+    }
+}
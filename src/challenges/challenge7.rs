@@ -0,0 +1,36 @@
+use super::Challenge;
+use crate::aes128::modes;
+use crate::encodings;
+use crate::error::Result;
+use crate::hexaa;
+
+pub struct Challenge7;
+
+impl Challenge for Challenge7 {
+    fn number(&self) -> u32 {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "AES in ECB mode"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &["challenge7"]
+    }
+
+    fn solve(&self, inputs: &[String]) -> Result<String> {
+        let encrypted = inputs[0]
+            .lines()
+            .map(encodings::b64_as_bytes)
+            .collect::<Result<Vec<Vec<u8>>>>()?
+            .concat();
+        let key = hexaa::bytes_to_u128(b"YELLOW SUBMARINE");
+        let decrypted = modes::ecb_decrypt(&encrypted, key)?;
+        Ok(String::from_utf8_lossy(&decrypted).into_owned())
+    }
+
+    fn expected(&self) -> u64 {
+        0x6755_9bda_eacc_9941
+    }
+}
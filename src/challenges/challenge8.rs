@@ -0,0 +1,40 @@
+use super::Challenge;
+use crate::aes128::modes::BLOCK_SIZE;
+use crate::block_analysis;
+use crate::encodings;
+use crate::error::Result;
+
+pub struct Challenge8;
+
+impl Challenge for Challenge8 {
+    fn number(&self) -> u32 {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Detect AES in ECB mode"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &["challenge8"]
+    }
+
+    fn solve(&self, inputs: &[String]) -> Result<String> {
+        let lines = inputs[0]
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| Ok((line, encodings::hex_as_bytes(line)?)))
+            .collect::<Result<Vec<(&str, Vec<u8>)>>>()?;
+        Ok(lines
+            .into_iter()
+            .max_by_key(|(_, bytes)| {
+                block_analysis::analyze(&[bytes], BLOCK_SIZE).repeated_blocks()
+            })
+            .map(|(line, _)| line.to_owned())
+            .unwrap_or_default())
+    }
+
+    fn expected(&self) -> u64 {
+        0xc5ca_fad2_ac21_3170
+    }
+}
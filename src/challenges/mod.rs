@@ -0,0 +1,155 @@
+use crate::error::Result;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+mod challenge1;
+mod challenge11;
+mod challenge12;
+mod challenge13;
+mod challenge14;
+mod challenge16;
+mod challenge2;
+mod challenge21;
+mod challenge22;
+mod challenge23;
+mod challenge24;
+mod challenge25;
+mod challenge26;
+mod challenge27;
+mod challenge28;
+mod challenge29;
+mod challenge3;
+mod challenge30;
+mod challenge4;
+mod challenge5;
+mod challenge6;
+mod challenge7;
+mod challenge8;
+
+/// A solved cryptopals challenge.
+/// The expected answer is kept as a fingerprint, so that the solutions are not spoiled,
+/// while regressions in the crate are still caught.
+pub trait Challenge {
+    fn number(&self) -> u32;
+
+    fn title(&self) -> &'static str;
+
+    /// Names of the files, under the `resources` directory, the challenge reads its input from.
+    fn inputs(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Solves the challenge, given the contents of its input files.
+    fn solve(&self, inputs: &[String]) -> Result<String>;
+
+    /// Fingerprint of the expected output of solve.
+    fn expected(&self) -> u64;
+}
+
+/// The outcome of running a challenge.
+/// A challenge which fails with an error has the error as its output, and does not pass.
+pub struct Outcome {
+    pub output: String,
+    pub passed: bool,
+}
+
+/// All the registered challenges, in order.
+pub fn all() -> Vec<Box<dyn Challenge>> {
+    vec![
+        Box::new(challenge1::Challenge1),
+        Box::new(challenge2::Challenge2),
+        Box::new(challenge3::Challenge3),
+        Box::new(challenge4::Challenge4),
+        Box::new(challenge5::Challenge5),
+        Box::new(challenge6::Challenge6),
+        Box::new(challenge7::Challenge7),
+        Box::new(challenge8::Challenge8),
+        Box::new(challenge11::Challenge11),
+        Box::new(challenge12::Challenge12),
+        Box::new(challenge13::Challenge13),
+        Box::new(challenge14::Challenge14),
+        Box::new(challenge16::Challenge16),
+        Box::new(challenge21::Challenge21),
+        Box::new(challenge22::Challenge22),
+        Box::new(challenge23::Challenge23),
+        Box::new(challenge24::Challenge24),
+        Box::new(challenge25::Challenge25),
+        Box::new(challenge26::Challenge26),
+        Box::new(challenge27::Challenge27),
+        Box::new(challenge28::Challenge28),
+        Box::new(challenge29::Challenge29),
+        Box::new(challenge30::Challenge30),
+    ]
+}
+
+pub fn get(number: u32) -> Option<Box<dyn Challenge>> {
+    all().into_iter().find(|c| c.number() == number)
+}
+
+/// Reads the inputs of the challenge, solves it, and verifies the output.
+pub fn run(challenge: &dyn Challenge) -> io::Result<Outcome> {
+    let inputs = challenge
+        .inputs()
+        .iter()
+        .map(|name| fs::read_to_string(resource(name)))
+        .collect::<io::Result<Vec<String>>>()?;
+    let outcome = match challenge.solve(&inputs) {
+        Ok(output) => Outcome {
+            passed: fingerprint(&output) == challenge.expected(),
+            output,
+        },
+        Err(e) => Outcome {
+            output: format!("error: {}", e),
+            passed: false,
+        },
+    };
+    Ok(outcome)
+}
+
+/// The base64 encoded secret which the oracles of challenges 12 and 14 append to the input.
+const SECRET_SUFFIX: &str = "Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkgaGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBqdXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUgYnkK";
+
+/// Resources are looked up relative to the crate, and not the working directory.
+fn resource(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "resources", name]
+        .iter()
+        .collect()
+}
+
+/// 64 bit FNV-1a hash of the output.
+pub fn fingerprint(output: &str) -> u64 {
+    output.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_should_match_fnv1a_reference_values() {
+        assert_eq!(fingerprint(""), 0xcbf29ce484222325);
+        assert_eq!(fingerprint("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn all_should_be_ordered_and_unique() {
+        let numbers: Vec<u32> = all().iter().map(|c| c.number()).collect();
+        assert!(numbers.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn all_should_pass() {
+        for challenge in all() {
+            let outcome = run(challenge.as_ref()).unwrap();
+            assert!(
+                outcome.passed,
+                "challenge {} produced unexpected output:\n{}",
+                challenge.number(),
+                outcome.output
+            );
+        }
+    }
+}
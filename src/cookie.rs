@@ -0,0 +1,83 @@
+//! Structured cookies of the form `key=value&key=value`.
+//!
+//! Keys and values cannot contain the metacharacters `&` and `=`;
+//! the encoder strips them, so that a value cannot inject fields of its own.
+
+use crate::error::{Error, Result};
+
+/// Parses a cookie into its key, value pairs, in order.
+/// ```
+/// assert_eq!(
+///     cryptopals::cookie::parse("foo=bar&baz=qux&zap=zazzle"),
+///     Ok(vec![
+///         ("foo".to_owned(), "bar".to_owned()),
+///         ("baz".to_owned(), "qux".to_owned()),
+///         ("zap".to_owned(), "zazzle".to_owned())
+///     ]));
+/// ```
+pub fn parse(cookie: &str) -> Result<Vec<(String, String)>> {
+    if cookie.is_empty() {
+        return Ok(vec![]);
+    }
+    cookie
+        .split('&')
+        .map(|field| match field.split_once('=') {
+            Some((key, value)) if !key.is_empty() && !value.contains('=') => {
+                Ok((key.to_owned(), value.to_owned()))
+            }
+            _ => Err(Error::Decoding(format!(
+                "malformed cookie field `{}`",
+                field
+            ))),
+        })
+        .collect()
+}
+
+/// Encodes the key, value pairs into a cookie, stripping the metacharacters from them.
+/// ```
+/// assert_eq!(
+///     cryptopals::cookie::encode(&[("email", "foo@bar.com&role=admin")]),
+///     "email=foo@bar.comroleadmin");
+/// ```
+pub fn encode<K: AsRef<str>, V: AsRef<str>>(fields: &[(K, V)]) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| format!("{}={}", sanitize(key.as_ref()), sanitize(value.as_ref())))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// Removes the metacharacters `&` and `=`.
+pub fn sanitize(s: &str) -> String {
+    s.chars().filter(|c| *c != '&' && *c != '=').collect()
+}
+
+/// The value of the first field with the key.
+pub fn get<'a>(fields: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_reject_malformed_fields() {
+        assert!(parse("foo=bar&baz").is_err());
+        assert!(parse("foo=bar&=qux").is_err());
+        assert!(parse("foo=bar=baz").is_err());
+        assert_eq!(parse("foo="), Ok(vec![("foo".to_owned(), "".to_owned())]));
+    }
+
+    #[test]
+    fn encode_should_round_trip_through_parse() {
+        let encoded = encode(&[("email", "a&b=c@d.com"), ("role", "user")]);
+        let fields = parse(&encoded).unwrap();
+        assert_eq!(get(&fields, "email"), Some("abc@d.com"));
+        assert_eq!(get(&fields, "role"), Some("user"));
+        assert_eq!(get(&fields, "uid"), None);
+    }
+}
@@ -0,0 +1,311 @@
+//! Known-plaintext (crib) recovery of repeating-key XOR keys.
+//!
+//! A repeating-key XOR ciphertext (see `encrypt::repeated_key_xor`) XOR'ed with the plaintext
+//! gives the key repeated over its whole length. So a crib - a piece of known plaintext -
+//! reveals the key bytes at the positions of the key it was encrypted with.
+//! For a key length L, the byte at position p of the ciphertext is encrypted with key[p % L],
+//! so cribs either agree with each other for a key length and offset, or rule it out.
+
+use crate::hexaa;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+/// A piece of known plaintext, at a known offset in the plaintext, or anywhere.
+#[derive(Clone, Debug)]
+pub struct Crib<'a> {
+    pub plaintext: &'a [u8],
+    pub offset: Option<usize>,
+}
+
+impl<'a> Crib<'a> {
+    /// A crib known to start at offset in the plaintext, e.g. a file header.
+    pub fn at(plaintext: &'a [u8], offset: usize) -> Self {
+        Crib {
+            plaintext,
+            offset: Some(offset),
+        }
+    }
+
+    /// A crib known to appear somewhere in the plaintext, e.g. a protocol banner.
+    pub fn anywhere(plaintext: &'a [u8]) -> Self {
+        Crib {
+            plaintext,
+            offset: None,
+        }
+    }
+}
+
+/// Parameters for the crib based key recovery.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Key lengths to consider.
+    pub key_sizes: RangeInclusive<usize>,
+    /// A crib with an unknown offset is only placed where at least this many of the key bytes
+    /// it reveals are confirmed, either by the crib itself or by previously placed cribs.
+    pub min_confirmations: usize,
+    /// Maximum number of hypotheses kept per key length.
+    pub max_hypotheses: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            key_sizes: 1..=40,
+            min_confirmations: 4,
+            max_hypotheses: 1024,
+        }
+    }
+}
+
+/// A key consistent with all the cribs.
+/// Key bytes that no crib revealed are None.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyHypothesis {
+    pub key: Vec<Option<u8>>,
+    /// Offsets the cribs were placed at, in the order the cribs were given.
+    pub offsets: Vec<usize>,
+}
+
+impl KeyHypothesis {
+    pub fn key_length(&self) -> usize {
+        self.key.len()
+    }
+
+    pub fn known_bytes(&self) -> usize {
+        self.key.iter().filter(|k| k.is_some()).count()
+    }
+
+    /// The key, if the cribs revealed all of its bytes.
+    pub fn complete_key(&self) -> Option<Vec<u8>> {
+        self.key.iter().copied().collect()
+    }
+
+    /// Decrypts the ciphertext with the known key bytes.
+    /// Bytes encrypted with unknown key bytes are None.
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Vec<Option<u8>> {
+        ciphertext
+            .iter()
+            .enumerate()
+            .map(|(i, c)| self.key[i % self.key.len()].map(|k| c ^ k))
+            .collect()
+    }
+}
+
+/// Derives the key bytes revealed by a crib at a known offset, for a key length.
+/// Returns None if the crib is inconsistent with that key length,
+/// i.e. if it reveals different values for the same key byte, or if the key length is zero.
+/// ```
+/// use cryptopals::{crib, encrypt};
+/// let ciphertext = encrypt::repeated_key_xor(b"GIF89a....", b"KEY").unwrap();
+/// assert_eq!(
+///     crib::key_from_known_plaintext(&ciphertext, b"GIF89a", 0, 3),
+///     Some(vec![Some(b'K'), Some(b'E'), Some(b'Y')]));
+/// ```
+pub fn key_from_known_plaintext(
+    ciphertext: &[u8],
+    plaintext: &[u8],
+    offset: usize,
+    key_length: usize,
+) -> Option<Vec<Option<u8>>> {
+    place(
+        &vec![None; key_length],
+        &keystream(ciphertext, plaintext, offset)?,
+        offset,
+    )
+    .map(|(key, _)| key)
+}
+
+/// Recovers all the repeating keys, of the key lengths in config, consistent with the cribs.
+/// Cribs at known offsets are placed first, then the cribs at unknown offsets
+/// are tried at every offset where they confirm enough of the key.
+/// Hypotheses are ranked with the most revealed key bytes first, then the shortest keys.
+/// Complete keys which merely repeat a shorter hypothesis are left out.
+pub fn recover_key(ciphertext: &[u8], cribs: &[Crib], config: &Config) -> Vec<KeyHypothesis> {
+    let mut order: Vec<usize> = (0..cribs.len()).collect();
+    order.sort_by_key(|i| cribs[*i].offset.is_none());
+
+    let mut hypotheses: Vec<KeyHypothesis> = config
+        .key_sizes
+        .clone()
+        .filter(|l| *l > 0)
+        .flat_map(|key_length| hypotheses_for_length(ciphertext, cribs, &order, key_length, config))
+        .collect();
+
+    let complete: Vec<Vec<u8>> = hypotheses.iter().filter_map(|h| h.complete_key()).collect();
+    hypotheses.retain(|h| {
+        h.complete_key().is_none_or(|key| {
+            !complete
+                .iter()
+                .any(|shorter| shorter.len() < key.len() && is_repetition_of(&key, shorter))
+        })
+    });
+    let mut seen = HashSet::new();
+    hypotheses.retain(|h| seen.insert(h.key.clone()));
+    hypotheses.sort_by(|a, b| {
+        b.known_bytes()
+            .cmp(&a.known_bytes())
+            .then(a.key_length().cmp(&b.key_length()))
+    });
+    hypotheses
+}
+
+fn hypotheses_for_length(
+    ciphertext: &[u8],
+    cribs: &[Crib],
+    order: &[usize],
+    key_length: usize,
+    config: &Config,
+) -> Vec<KeyHypothesis> {
+    let start = KeyHypothesis {
+        key: vec![None; key_length],
+        offsets: vec![0; cribs.len()],
+    };
+    order.iter().fold(vec![start], |hypotheses, index| {
+        let crib = &cribs[*index];
+        let last_offset = match ciphertext.len().checked_sub(crib.plaintext.len()) {
+            Some(last_offset) => last_offset,
+            None => return vec![],
+        };
+        let offsets: Vec<usize> = match crib.offset {
+            Some(offset) => vec![offset],
+            None => (0..=last_offset).collect(),
+        };
+        let mut placed = vec![];
+        for hypothesis in hypotheses.iter() {
+            for offset in offsets.iter() {
+                let keystream = match keystream(ciphertext, crib.plaintext, *offset) {
+                    Some(keystream) => keystream,
+                    None => continue,
+                };
+                match place(&hypothesis.key, &keystream, *offset) {
+                    Some((key, confirmations))
+                        if crib.offset.is_some() || confirmations >= config.min_confirmations =>
+                    {
+                        let mut offsets = hypothesis.offsets.clone();
+                        offsets[*index] = *offset;
+                        placed.push(KeyHypothesis { key, offsets });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        placed.truncate(config.max_hypotheses);
+        placed
+    })
+}
+
+/// The key bytes revealed by the plaintext at offset, i.e. ciphertext XOR plaintext.
+fn keystream(ciphertext: &[u8], plaintext: &[u8], offset: usize) -> Option<Vec<u8>> {
+    let encrypted = ciphertext.get(offset..offset.checked_add(plaintext.len())?)?;
+    Some(hexaa::xor_bytes(encrypted, plaintext))
+}
+
+/// Writes the keystream into the key, starting from the key byte for offset.
+/// Returns the new key and the number of key bytes which were already known and agreed,
+/// or None if any of them disagreed, or if the key is empty.
+fn place(key: &[Option<u8>], keystream: &[u8], offset: usize) -> Option<(Vec<Option<u8>>, usize)> {
+    if key.is_empty() {
+        return None;
+    }
+    let mut key = key.to_vec();
+    let length = key.len();
+    let mut confirmations = 0;
+    for (i, byte) in keystream.iter().enumerate() {
+        let slot = &mut key[(offset + i) % length];
+        match slot {
+            Some(known) if known == byte => confirmations += 1,
+            Some(_) => return None,
+            None => *slot = Some(*byte),
+        }
+    }
+    Some((key, confirmations))
+}
+
+fn is_repetition_of(key: &[u8], shorter: &[u8]) -> bool {
+    key.len().is_multiple_of(shorter.len())
+        && key.chunks(shorter.len()).all(|chunk| chunk == shorter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encrypt;
+
+    const TEXT: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\nthe quick brown fox \
+        jumps over the lazy dog and the lazy dog does not mind at all";
+
+    fn encrypted(key: &[u8]) -> Vec<u8> {
+        encrypt::repeated_key_xor(TEXT, key).unwrap()
+    }
+
+    #[test]
+    fn recover_key_should_find_the_key_from_a_header() {
+        let ciphertext = encrypted(b"s3cr3t");
+        let hypotheses = recover_key(
+            &ciphertext,
+            &[Crib::at(b"HTTP/1.1 200 OK", 0)],
+            &Config::default(),
+        );
+        let complete: Vec<Vec<u8>> = hypotheses.iter().filter_map(|h| h.complete_key()).collect();
+        assert!(complete.contains(&b"s3cr3t".to_vec()));
+        // a 12 byte key would only repeat the 6 byte one
+        assert!(!complete.contains(&b"s3cr3ts3cr3t".to_vec()));
+    }
+
+    #[test]
+    fn recover_key_should_place_cribs_at_unknown_offsets() {
+        let ciphertext = encrypted(b"KEY");
+        let config = Config {
+            key_sizes: 3..=3,
+            ..Config::default()
+        };
+        let hypotheses = recover_key(&ciphertext, &[Crib::anywhere(b"quick brown")], &config);
+        assert_eq!(hypotheses.len(), 1);
+        assert_eq!(hypotheses[0].complete_key(), Some(b"KEY".to_vec()));
+        assert_eq!(hypotheses[0].offsets, vec![49]);
+    }
+
+    #[test]
+    fn recover_key_should_combine_cribs() {
+        let ciphertext = encrypted(b"a longer key");
+        let cribs = [Crib::at(b"HTTP/1", 0), Crib::anywhere(b"lazy dog")];
+        let config = Config {
+            key_sizes: 12..=12,
+            ..Config::default()
+        };
+        let hypotheses = recover_key(&ciphertext, &cribs, &config);
+        let decrypted = hypotheses[0].decrypt(&ciphertext);
+        assert_eq!(decrypted[0], Some(b'H'));
+        assert!(hypotheses[0].known_bytes() > 6);
+    }
+
+    #[test]
+    fn recover_key_should_reject_inconsistent_cribs() {
+        let ciphertext = encrypted(b"KEY");
+        let cribs = [Crib::at(b"HTTP/1.1", 0), Crib::at(b"GIF89a", 3)];
+        let config = Config {
+            key_sizes: 3..=3,
+            ..Config::default()
+        };
+        assert!(recover_key(&ciphertext, &cribs, &config).is_empty());
+    }
+
+    #[test]
+    fn key_from_known_plaintext_should_reject_non_periodic_keystream() {
+        let ciphertext = encrypted(b"KEY");
+        assert_eq!(
+            key_from_known_plaintext(&ciphertext, b"HTTP/1.1", 0, 4),
+            None
+        );
+    }
+
+    #[test]
+    fn key_from_known_plaintext_should_reject_a_zero_key_length_or_a_huge_offset() {
+        let ciphertext = encrypted(b"KEY");
+        assert_eq!(key_from_known_plaintext(&ciphertext, b"HTTP", 0, 0), None);
+        assert_eq!(
+            key_from_known_plaintext(&ciphertext, b"HTTP", usize::MAX, 3),
+            None
+        );
+    }
+}
@@ -1,21 +1,34 @@
 use crate::encodings;
+use crate::error::{Error, Result};
 use crate::hexaa;
-use std::collections::HashMap;
+use crate::key_length;
+use crate::scoring::{LetterFrequency, Scorer};
 
-pub fn find_vignere_key(bytes: &Vec<u8>) -> Vec<u8> {
-    let key_size = find_optimum_key_size(bytes);
+/// Finds the key of a repeating-key XOR encrypted English text.
+pub fn find_vignere_key(bytes: &[u8]) -> Result<Vec<u8>> {
+    find_vignere_key_with(bytes, &LetterFrequency::new())
+}
+
+/// Finds the key of a repeating-key XOR encryption,
+/// solving every column of the transposed ciphertext with the given scorer.
+pub fn find_vignere_key_with<S: Scorer>(bytes: &[u8], scorer: &S) -> Result<Vec<u8>> {
+    let key_size = find_optimum_key_size(bytes)?;
+    Ok(solve_repeating_key_xor(bytes, key_size, scorer))
+}
 
-    chunk_and_transpose(&bytes, key_size)
+/// Finds the key, of a known size, of a repeating-key XOR encryption,
+/// solving every column of the transposed ciphertext with the given scorer.
+pub fn solve_repeating_key_xor<S: Scorer>(bytes: &[u8], key_size: usize, scorer: &S) -> Vec<u8> {
+    chunk_and_transpose(bytes, key_size)
         .iter()
-        .map(single_byte_xor)
-        .map(|x| x.unwrap().0)
+        .map(|column| single_byte_xor(column, scorer).0)
         .collect()
 }
 
 /// Groups the vector into {{chunk_size}}d chunks and
 /// then transposes the vectors, to give {{chunk_size}}
 /// number of vectors.
-fn chunk_and_transpose<T: Copy>(v: &Vec<T>, chunk_size: usize) -> Vec<Vec<T>> {
+fn chunk_and_transpose<T: Copy>(v: &[T], chunk_size: usize) -> Vec<Vec<T>> {
     let mut transposed_blocks = (0..chunk_size).map(|_| vec![]).collect();
     v.chunks(chunk_size).for_each(|block| {
         block
@@ -26,15 +39,13 @@ fn chunk_and_transpose<T: Copy>(v: &Vec<T>, chunk_size: usize) -> Vec<Vec<T>> {
     transposed_blocks
 }
 
-/// Iterates over key_sizes from 2 to 40, and returns the key_size for which
-/// the average normalized hamming distance is minimum.
-pub fn find_optimum_key_size(s: &Vec<u8>) -> usize {
-    (2..41)
-        .into_iter()
-        .map(|k| (k, encodings::average_normalized_hamming_distance(k, s)))
-        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-        .unwrap()
-        .0
+/// Iterates over key_sizes from 2 to 40, and returns the best ranked key_size
+/// of the statistical key length analysis.
+/// Fails if the input is too short for any of those key sizes.
+pub fn find_optimum_key_size(s: &[u8]) -> Result<usize> {
+    key_length::analyze(s, &key_length::Config::default())
+        .best()
+        .ok_or_else(|| Error::Cryptanalysis("too few bytes to find the key size".to_owned()))
 }
 
 /// Runs the input hex-strings through single-char XORs
@@ -42,93 +53,53 @@ pub fn find_optimum_key_size(s: &Vec<u8>) -> usize {
 /// and returns the string with highest score
 /// calculated as weighted sum of letter frequencies of english alphabet.
 /// Letters [a-z] and space [' '] are included in the calculated frequency score.
+/// Fails if any of the strings is not valid hex, or if there are no strings.
 /// TODO this is not a utility; move this to the challenge (3, 4) specific code instead
-pub fn single_char_xor(ss: &[&str]) -> Option<(u8, String)> {
+pub fn single_char_xor(ss: &[&str]) -> Result<(u8, String)> {
+    let scorer = LetterFrequency::new();
     ss.iter()
         .map(|s| encodings::hex_as_bytes(s))
-        .map(|bytes| single_byte_xor(&bytes))
-        .filter(|result| result.is_some())
-        .map(|some_result| some_result.unwrap())
-        .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
-        .map(|x| (x.0, x.1))
-}
-
-/// Runs the input vector of bytes through repeated_byte_xor with all the possible bytes (keys)
-/// returning the key, score, and the xor'ed output for the key with highest score.
-/// Score is calculated based on English language letter frequencies.
-fn single_byte_xor(bytes: &Vec<u8>) -> Option<(u8, String, f32)> {
-    let scorer = LetterFrequency::new();
-    (0..=0xff)
-        .map(|key| {
-            (
-                key,
-                String::from_utf8(hexaa::repeated_byte_xor(&bytes, key)),
-            )
-        })
-        .filter(|(_, result)| result.is_ok())
-        .map(|(key, result)| (key, result.unwrap()))
-        .map(|(key, string)| (key, string.to_string(), scorer.score(&string)))
-        .max_by(|(_, _, score1), (_, _, score2)| score1.partial_cmp(score2).unwrap())
+        .collect::<Result<Vec<Vec<u8>>>>()?
+        .iter()
+        .map(|bytes| single_byte_xor(bytes, &scorer))
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|x| (x.0, String::from_utf8_lossy(&x.1).into_owned()))
+        .ok_or_else(|| Error::Cryptanalysis("no strings to decrypt".to_owned()))
 }
 
-struct LetterFrequency {
-    data: HashMap<char, f32>,
+/// Runs the input bytes through repeated_byte_xor with all the possible bytes (keys)
+/// returning the key, the xor'ed output, and the score for the key with highest score.
+/// Every output is a candidate, whether or not it is valid text.
+/// ```
+/// use cryptopals::decrypt::single_byte_xor;
+/// use cryptopals::scoring::LetterFrequency;
+/// let encrypted: Vec<u8> = b"attack at dawn".iter().map(|b| b ^ 0x9c).collect();
+/// let (key, decrypted, _) = single_byte_xor(&encrypted, &LetterFrequency::new());
+/// assert_eq!(key, 0x9c);
+/// assert_eq!(decrypted, b"attack at dawn");
+/// ```
+pub fn single_byte_xor<S: Scorer>(bytes: &[u8], scorer: &S) -> (u8, Vec<u8>, f32) {
+    single_byte_xor_candidates(bytes, scorer)
+        .into_iter()
+        .next()
+        .map(|(key, score)| (key, hexaa::repeated_byte_xor(bytes, key), score))
+        .expect("There are 256 candidate keys.")
 }
 
-impl LetterFrequency {
-    fn new() -> Self {
-        // source : https://en.wikipedia.org/wiki/Letter_frequency
-        LetterFrequency {
-            data: vec![
-                ('a', 8.497),
-                ('b', 1.492),
-                ('c', 2.202),
-                ('d', 4.253),
-                ('e', 11.162),
-                ('f', 2.228),
-                ('g', 2.015),
-                ('h', 6.094),
-                ('i', 7.546),
-                ('j', 0.153),
-                ('k', 1.292),
-                ('l', 4.025),
-                ('m', 2.406),
-                ('n', 6.749),
-                ('o', 7.507),
-                ('p', 1.929),
-                ('q', 0.095),
-                ('r', 7.587),
-                ('s', 6.327),
-                ('t', 9.356),
-                ('u', 2.758),
-                ('v', 0.978),
-                ('w', 2.560),
-                ('x', 0.150),
-                ('y', 1.994),
-                ('z', 0.077),
-                (' ', 12.),
-            ]
-            .into_iter()
-            .collect(),
-        }
-    }
-
-    fn score(&self, s: &str) -> f32 {
-        s.chars()
-            .map(|c| {
-                *self
-                    .data
-                    .get(&c.to_ascii_lowercase())
-                    .copied()
-                    .get_or_insert(0.)
-            })
-            .fold(0., |acc, x| acc + x)
-    }
+/// Scores the input bytes XOR'ed with every possible byte (key),
+/// returning all the keys along with their scores, best first.
+pub fn single_byte_xor_candidates<S: Scorer>(bytes: &[u8], scorer: &S) -> Vec<(u8, f32)> {
+    let mut candidates: Vec<(u8, f32)> = (0..=0xff)
+        .map(|key| (key, scorer.score(&hexaa::repeated_byte_xor(bytes, key))))
+        .collect();
+    candidates.sort_by(|(_, score1), (_, score2)| score2.total_cmp(score1));
+    candidates
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scoring::ByteHistogram;
 
     #[test]
     fn chunk_and_transpose_should_do_so() {
@@ -138,4 +109,49 @@ mod tests {
             vec![vec![0, 3, 6, 9], vec![1, 4, 7, 0], vec![2, 5, 8]]
         );
     }
+
+    #[test]
+    fn single_byte_xor_should_recover_non_utf8_plaintext() {
+        let sample: Vec<u8> = (0..64).flat_map(|i| vec![0x00, 0x00, 0xff, i]).collect();
+        let plaintext: Vec<u8> = vec![0x00, 0xff, 0x00, 0x00, 0x12, 0x00, 0xff, 0x00];
+        let encrypted = hexaa::repeated_byte_xor(&plaintext, 0x41);
+
+        let (key, decrypted, _) = single_byte_xor(&encrypted, &ByteHistogram::from_sample(&sample));
+
+        assert!(String::from_utf8(plaintext.clone()).is_err());
+        assert_eq!(key, 0x41);
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn single_byte_xor_candidates_should_rank_every_key() {
+        let encrypted = hexaa::repeated_byte_xor(b"hello there", 0x07);
+        let candidates = single_byte_xor_candidates(&encrypted, &LetterFrequency::new());
+        assert_eq!(candidates.len(), 256);
+        assert_eq!(candidates[0].0, 0x07);
+    }
+
+    #[test]
+    fn find_vignere_key_with_should_not_panic_on_binary_columns() {
+        let plaintext: Vec<u8> = (0..=255u8).cycle().take(600).collect();
+        let encrypted = crate::encrypt::repeated_key_xor(&plaintext, &[0x80, 0xc3, 0x01]).unwrap();
+        let key = find_vignere_key_with(&encrypted, &ByteHistogram::from_sample(&[0x00]));
+        assert!(!key.unwrap().is_empty());
+    }
+
+    #[test]
+    fn find_vignere_key_should_fail_for_too_short_input() {
+        assert!(matches!(
+            find_vignere_key(b"abc"),
+            Err(Error::Cryptanalysis(_))
+        ));
+    }
+
+    #[test]
+    fn single_char_xor_should_fail_for_invalid_hex() {
+        assert!(matches!(
+            single_char_xor(&["1b37", "zz"]),
+            Err(Error::Decoding(_))
+        ));
+    }
 }
@@ -0,0 +1,170 @@
+//! Byte-at-a-time decryption of a secret which an ECB encryption oracle
+//! appends to the attacker's input (cryptopals challenges 12 and 14).
+//!
+//! With an input one byte short of a block, the last byte of that block is the
+//! first byte of the secret; encrypting the same short input followed by every
+//! possible byte finds the one which gives the same block. Shortening the input
+//! by a byte at a time moves the following bytes of the secret into view.
+
+use crate::aes128::modes;
+use crate::block_analysis::{self, Mode};
+use crate::error::{Error, Result};
+use crate::oracle::{Counting, EncryptionOracle};
+use crate::random::Random;
+
+/// The target: encrypts, under a fixed random key, a fixed prefix, the input, and the secret.
+pub struct SecretSuffixOracle {
+    key: u128,
+    prefix: Vec<u8>,
+    secret: Vec<u8>,
+}
+
+impl SecretSuffixOracle {
+    /// The oracle of challenge 12, without a prefix.
+    pub fn new(secret: &[u8], random: &mut Random) -> Self {
+        SecretSuffixOracle {
+            key: random.next_u128(),
+            prefix: vec![],
+            secret: secret.to_vec(),
+        }
+    }
+
+    /// The oracle of challenge 14, with a prefix of a random count (up to 64) of random bytes.
+    pub fn with_random_prefix(secret: &[u8], random: &mut Random) -> Self {
+        let length = random.range(0, 65);
+        SecretSuffixOracle {
+            prefix: random.bytes(length),
+            ..Self::new(secret, random)
+        }
+    }
+}
+
+impl EncryptionOracle for SecretSuffixOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let input = [&self.prefix[..], plaintext, &self.secret[..]].concat();
+        Ok(modes::ecb_encrypt(&input, self.key))
+    }
+}
+
+/// The outcome of the attack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recovery {
+    pub secret: Vec<u8>,
+    pub block_size: usize,
+    /// Length of the data the oracle prepends to the input.
+    pub prefix_length: usize,
+    /// Number of queries made to the oracle.
+    pub queries: u64,
+}
+
+/// Recovers the secret appended to the input by an ECB encryption oracle.
+/// The oracle may prepend data to the input, as long as it is the same for every query.
+pub fn recover_secret<O: EncryptionOracle>(oracle: &O) -> Result<Recovery> {
+    let oracle = Counting::new(oracle);
+    let (block_size, unknown_length) = block_analysis::find_block_size(&oracle)?;
+    if block_analysis::detect_mode(&oracle, block_size)?.mode != Mode::Ecb {
+        return Err(Error::Cryptanalysis(
+            "oracle does not encrypt in ECB mode".to_owned(),
+        ));
+    }
+    let prefix_length = block_analysis::find_prefix_length(&oracle, block_size)?;
+    let secret_length = unknown_length
+        .checked_sub(prefix_length)
+        .ok_or_else(|| Error::Cryptanalysis("prefix is longer than the output".to_owned()))?;
+
+    // input bytes which fill up the last block of the prefix
+    let alignment = (block_size - prefix_length % block_size) % block_size;
+    let first_block = (prefix_length + alignment) / block_size;
+    let mut secret = vec![];
+    while secret.len() < secret_length {
+        let padding = block_size - 1 - secret.len() % block_size;
+        let block = first_block + secret.len() / block_size;
+        let target = oracle.encrypt(&vec![0; alignment + padding])?;
+        let target = block_analysis::nth_block(&target, block, block_size)
+            .ok_or_else(|| Error::Cryptanalysis("the secret ended early".to_owned()))?;
+
+        // the block size minus one bytes before the unknown byte, in the input
+        let known: Vec<u8> = std::iter::repeat_n(0, block_size - 1)
+            .chain(secret.iter().copied())
+            .skip(secret.len())
+            .collect();
+        let mut found = None;
+        for candidate in candidates() {
+            let mut input = vec![0; alignment];
+            input.extend_from_slice(&known);
+            input.push(candidate);
+            if block_analysis::nth_block(&oracle.encrypt(&input)?, first_block, block_size)
+                == Some(target)
+            {
+                found = Some(candidate);
+                break;
+            }
+        }
+        match found {
+            Some(byte) => secret.push(byte),
+            None => {
+                return Err(Error::Cryptanalysis(format!(
+                    "no byte matches byte {} of the secret",
+                    secret.len()
+                )))
+            }
+        }
+    }
+    Ok(Recovery {
+        secret,
+        block_size,
+        prefix_length,
+        queries: oracle.queries(),
+    })
+}
+
+/// All the bytes, the ones most frequent in English text first,
+/// so that a text secret is recovered with fewer queries.
+fn candidates() -> impl Iterator<Item = u8> {
+    const COMMON: &[u8] = b" etaoinshrdlucmfwypvbgkjqxzETAOINSHRDLUCMFWYPVBGKJQXZ.,'\n0123456789";
+    COMMON
+        .iter()
+        .copied()
+        .chain((0..=255).filter(|b| !COMMON.contains(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"Rollin' in my 5.0\nWith my rag-top down so my hair can blow\n\x00\xff";
+
+    #[test]
+    fn recover_secret_should_decrypt_the_secret_without_a_prefix() {
+        let oracle = SecretSuffixOracle::new(SECRET, &mut Random::with_seed(12));
+        let recovery = recover_secret(&oracle).unwrap();
+        assert_eq!(recovery.secret, SECRET.to_vec());
+        assert_eq!((recovery.block_size, recovery.prefix_length), (16, 0));
+        assert!(recovery.queries > SECRET.len() as u64);
+    }
+
+    #[test]
+    fn recover_secret_should_handle_prefixes_of_any_length() {
+        let mut random = Random::with_seed(14);
+        for prefix_length in [1, 15, 16, 17, 40] {
+            let oracle = SecretSuffixOracle {
+                prefix: random.bytes(prefix_length),
+                ..SecretSuffixOracle::new(&SECRET[..20], &mut random)
+            };
+            let recovery = recover_secret(&oracle).unwrap();
+            assert_eq!(recovery.prefix_length, prefix_length);
+            assert_eq!(recovery.secret, SECRET[..20].to_vec());
+        }
+    }
+
+    #[test]
+    fn recover_secret_should_reject_cbc_oracles() {
+        let oracle = crate::oracle::from_fn(|p: &[u8]| {
+            Ok(modes::cbc_encrypt(&[p, b"secret"].concat(), 1, 2))
+        });
+        assert!(matches!(
+            recover_secret(&oracle),
+            Err(Error::Cryptanalysis(_))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+//! Forging an ECB encrypted cookie by splicing ciphertext blocks (cryptopals challenge 13).
+//!
+//! ECB encrypts every block independently, so blocks cut from different ciphertexts
+//! under the same key can be pasted together into a ciphertext that decrypts to
+//! the concatenation of their plaintexts. An attacker who controls a field of the
+//! cookie can get any block encrypted, e.g. `admin` followed by valid padding,
+//! and push the value to be replaced into a block of its own.
+
+use crate::aes128::modes;
+use crate::block_analysis;
+use crate::cookie;
+use crate::error::{Error, Result};
+use crate::oracle::{Counting, EncryptionOracle};
+use crate::padding;
+use crate::random::Random;
+
+/// Issues encrypted user profiles, `email=...&uid=10&role=user`, and reads them back.
+pub struct ProfileService {
+    key: u128,
+}
+
+impl ProfileService {
+    pub fn new(random: &mut Random) -> Self {
+        ProfileService {
+            key: random.next_u128(),
+        }
+    }
+
+    /// The encoded profile of a new user with the email.
+    /// ```
+    /// use cryptopals::ecb_cut_and_paste::ProfileService;
+    /// assert_eq!(
+    ///     ProfileService::profile_for("foo@bar.com&role=admin"),
+    ///     "email=foo@bar.comroleadmin&uid=10&role=user");
+    /// ```
+    pub fn profile_for(email: &str) -> String {
+        cookie::encode(&[("email", email), ("uid", "10"), ("role", "user")])
+    }
+
+    pub fn encrypted_profile_for(&self, email: &str) -> Vec<u8> {
+        modes::ecb_encrypt(Self::profile_for(email).as_bytes(), self.key)
+    }
+
+    /// Decrypts and parses a profile.
+    pub fn decrypt_profile(&self, ciphertext: &[u8]) -> Result<Vec<(String, String)>> {
+        let plaintext = modes::ecb_decrypt(ciphertext, self.key)?;
+        let plaintext = String::from_utf8(plaintext)
+            .map_err(|_| Error::Decoding("profile is not valid UTF-8".to_owned()))?;
+        cookie::parse(&plaintext)
+    }
+
+    pub fn is_admin(&self, ciphertext: &[u8]) -> Result<bool> {
+        Ok(cookie::get(&self.decrypt_profile(ciphertext)?, "role") == Some("admin"))
+    }
+}
+
+/// The email is the attacker's input.
+impl EncryptionOracle for ProfileService {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.encrypted_profile_for(&String::from_utf8_lossy(plaintext)))
+    }
+}
+
+/// A forged ciphertext, and the number of queries it took.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forgery {
+    pub ciphertext: Vec<u8>,
+    pub queries: u64,
+}
+
+/// Forges a ciphertext in which the value at the very end of the plaintext,
+/// `current` (e.g. `user`), is replaced by `forged` (e.g. `admin`).
+/// The oracle should encrypt in ECB mode, with its input somewhere in the middle
+/// of the plaintext, and both values should be shorter than a block.
+pub fn forge_last_value<O: EncryptionOracle>(
+    oracle: &O,
+    current: &[u8],
+    forged: &[u8],
+) -> Result<Forgery> {
+    let oracle = Counting::new(oracle);
+    let (block_size, added_length) = block_analysis::find_block_size(&oracle)?;
+    if current.len() >= block_size || forged.len() >= block_size {
+        return Err(Error::Cryptanalysis(
+            "values should be shorter than a block".to_owned(),
+        ));
+    }
+    let prefix_length = block_analysis::find_prefix_length(&oracle, block_size)?;
+    let suffix_length = added_length.checked_sub(prefix_length).ok_or_else(|| {
+        Error::Cryptanalysis("the prefix found is longer than the data added".to_owned())
+    })?;
+
+    // a block of its own with the forged value, validly padded as the last block
+    let alignment = (block_size - prefix_length % block_size) % block_size;
+    let mut input = vec![b'A'; alignment];
+    input.extend(padding::pkcs7_pad(forged, block_size));
+    let encrypted = oracle.encrypt(&input)?;
+    let forged_block = (prefix_length + alignment) / block_size;
+    let forged_block =
+        block_analysis::nth_block(&encrypted, forged_block, block_size).ok_or_else(|| {
+            Error::Cryptanalysis("the ciphertext ended before the forged block".to_owned())
+        })?;
+
+    // input long enough for the current value to start the last block
+    let before_current = (prefix_length + suffix_length)
+        .checked_sub(current.len())
+        .ok_or_else(|| {
+            Error::Cryptanalysis("the current value is longer than the data added".to_owned())
+        })?;
+    let filler = (block_size - before_current % block_size) % block_size;
+    let mut ciphertext = oracle.encrypt(&vec![b'A'; filler])?;
+    let without_last_block = ciphertext
+        .len()
+        .checked_sub(block_size)
+        .ok_or_else(|| Error::Cryptanalysis("the ciphertext is shorter than a block".to_owned()))?;
+    ciphertext.truncate(without_last_block);
+    ciphertext.extend_from_slice(forged_block);
+    Ok(Forgery {
+        ciphertext,
+        queries: oracle.queries(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle;
+
+    #[test]
+    fn forge_last_value_should_make_an_admin_profile() {
+        let service = ProfileService::new(&mut Random::with_seed(13));
+        assert!(!service
+            .is_admin(&service.encrypted_profile_for("a@b.com"))
+            .unwrap());
+
+        let forgery = forge_last_value(&service, b"user", b"admin").unwrap();
+        let profile = service.decrypt_profile(&forgery.ciphertext).unwrap();
+        assert_eq!(cookie::get(&profile, "role"), Some("admin"));
+        assert_eq!(cookie::get(&profile, "uid"), Some("10"));
+        assert!(forgery.queries > 0);
+    }
+
+    #[test]
+    fn forge_last_value_should_fail_when_the_current_value_does_not_fit() {
+        // the oracle adds 3 bytes in all, fewer than the current value
+        let oracle = oracle::from_fn(|input: &[u8]| {
+            Ok(modes::ecb_encrypt(&[b"a=", input, b"b"].concat(), 7))
+        });
+        assert!(matches!(
+            forge_last_value(&oracle, b"user", b"admin"),
+            Err(Error::Cryptanalysis(_))
+        ));
+    }
+
+    #[test]
+    fn profile_for_should_not_let_the_email_inject_fields() {
+        let service = ProfileService::new(&mut Random::with_seed(1));
+        let ciphertext = service.encrypted_profile_for("x@y.com&role=admin");
+        assert!(!service.is_admin(&ciphertext).unwrap());
+    }
+}
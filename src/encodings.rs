@@ -1,14 +1,15 @@
+//! For the purposes of readability, note the following definitions.
+//! hex : means the string form of a hexadecimal number. E.g. "0259acef". (It does not represent the underlying hexadecimal bytes, nor the ASCII bytes)
+//! b64 : means the string form of a base-64 number. E.g. "8QoRve8=". (Again, it does not represent the underlying binary format, nor the ASCII bytes)
+//! hex_ascii : means the ASCII bytes corresponding to a hex string. This is same as hex, only difference being - type is integer and not string.
+//! b64_ascii : means the ASCII bytes corresponding to a b84 string.
+//! byte : anywhere refers to binary. Its represented as a u8, or a Vec<u8>.
+//! So, a typical conversion is like: hex/b64 (string) ===> hex_ascii/b64_ascii (Vec<u8>) ===> bytes (Vec<u8>)
+
+use crate::error::{Error, Result};
 use crate::hexaa;
 use log::debug;
 
-/// For the purposes of readability, note the following definitions.
-/// hex : means the string form of a hexadecimal number. E.g. "0259acef". (It does not represent the underlying hexadecimal bytes, nor the ASCII bytes)
-/// b64 : means the string form of a base-64 number. E.g. "8QoRve8=". (Again, it does not represent the underlying binary format, nor the ASCII bytes)
-/// hex_ascii : means the ASCII bytes corresponding to a hex string. This is same as hex, only difference being - type is integer and not string.
-/// b64_ascii : means the ASCII bytes corresponding to a b84 string.
-/// byte : anywhere refers to binary. Its represented as a u8, or a Vec<u8>.
-/// So, a typical conversion is like: hex/b64 (string) ===> hex_ascii/b64_ascii (Vec<u8>) ===> bytes (Vec<u8>)
-
 /// Padder provides an implementation of pad_b64 function for Vec<u8>
 /// pad_b64 provides a fluent way of padding a Vec representing a base64 string
 /// with sufficient number of pad characters ('=')
@@ -18,57 +19,73 @@ trait Padder {
 
 impl Padder for Vec<u8> {
     fn pad_b64(mut self, count: usize) -> Self {
-        let pad = '=' as u8;
         let len = self.len();
-        for i in (len - count)..len {
-            self[i] = pad;
-        }
+        self[len - count..].iter_mut().for_each(|b| *b = b'=');
         self
     }
 }
 
 // Calculates normalized hamming distance and averages it over 4 slice of key_size size
-pub fn average_normalized_hamming_distance(key_size: usize, v: &Vec<u8>) -> f32 {
+// Fails if the input does not have at least two slices of key_size size.
+pub fn average_normalized_hamming_distance(key_size: usize, v: &[u8]) -> Result<f32> {
     let ranges: Vec<_> = (1..5)
         .map(|x| x * key_size)
         .filter(|x| *x < v.len())
-        .map(|x| (x - key_size..x))
+        .map(|x| x - key_size..x)
         .collect();
 
     let combinations = combine(&ranges, &ranges);
+    if combinations.is_empty() {
+        return Err(Error::Cryptanalysis(format!(
+            "{} bytes are too few to compare slices of {} bytes",
+            v.len(),
+            key_size
+        )));
+    }
     let combinations_count = combinations.len() as f32;
 
-    combinations
+    Ok(combinations
+        .into_iter()
+        .map(|(p, q)| normalized_hamming_distance(&v[p.clone()], &v[q.clone()]))
+        .collect::<Result<Vec<f32>>>()?
         .into_iter()
-        .map(|(p, q)| normalized_hamming_distance(&v[p.clone()].to_vec(), &v[q.clone()].to_vec()))
         .sum::<f32>()
-        / combinations_count
+        / combinations_count)
 }
 
 /// Calculates the normalized hammind distance between two strings,
 /// by dividing the hamming distance by the number of bytes in the strings
 // TODO : Should move these implementations to hexaa module
-fn normalized_hamming_distance(b1: &Vec<u8>, b2: &Vec<u8>) -> f32 {
-    assert!(b1.len() == b2.len());
-
-    let x = hexaa::xor_bytes(&b1, &b2);
-    hexaa::count_set_bits_in_bytes(x) as f32 / b1.len() as f32
+fn normalized_hamming_distance(b1: &[u8], b2: &[u8]) -> Result<f32> {
+    Ok(bytes_hamming_distance(b1, b2)? as f32 / b1.len() as f32)
 }
 
 /// Calculates hamming distance between the two given strings
-pub fn hamming_distance(s1: &str, s2: &str) -> u32 {
-    assert!(s1.len() == s2.len());
+/// Fails if the strings are of different lengths.
+pub fn hamming_distance(s1: &str, s2: &str) -> Result<u32> {
+    bytes_hamming_distance(s1.as_bytes(), s2.as_bytes())
+}
 
-    let b1 = s1.as_bytes().to_vec();
-    let b2 = s2.as_bytes().to_vec();
+/// Calculates hamming distance between the two given byte slices
+/// Fails if the slices are of different lengths.
+pub fn bytes_hamming_distance(b1: &[u8], b2: &[u8]) -> Result<u32> {
+    check_equal_lengths(b1.len(), b2.len())?;
 
-    let x = hexaa::xor_bytes(&b1, &b2);
-    hexaa::count_set_bits_in_bytes(x)
+    let x = hexaa::xor_bytes(b1, b2);
+    Ok(hexaa::count_set_bits_in_bytes(x))
+}
+
+fn check_equal_lengths(expected: usize, actual: usize) -> Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch { expected, actual })
+    }
 }
 
 /// Takes in two vectors, and zips them in cross product fashion, yielding every combination possible.
 /// Cross product is done so as not to creates index-wise duplicate combinations (e.g. (a, b) and (b, a))
-fn combine<'a, 'b, T>(v1: &'a Vec<T>, v2: &'b Vec<T>) -> Vec<(&'a T, &'b T)> {
+fn combine<'a, 'b, T>(v1: &'a [T], v2: &'b [T]) -> Vec<(&'a T, &'b T)> {
     v1.iter()
         .enumerate()
         .flat_map(|(index1, item1)| {
@@ -90,31 +107,31 @@ fn combine<'a, 'b, T>(v1: &'a Vec<T>, v2: &'b Vec<T>) -> Vec<(&'a T, &'b T)> {
 /// assert_eq!(cryptopals::encodings::xor_hexes(
 ///         "0259acef",
 ///         "bd134678"),
-///     "bf4aea97");
+///     Ok("bf4aea97".to_owned()));
+/// ```
 /// ```
-/// ```should_panic
-/// cryptopals::encodings::xor_hexes("02", "bd1"); // length constraint
+/// assert!(cryptopals::encodings::xor_hexes("02", "bd1").is_err()); // length constraint
 /// ```
-/// ```should_panic
-/// cryptopals::encodings::xor_hexes("02g", "bd1"); // invalid hex digit
 /// ```
-pub fn xor_hexes(hex1: &str, hex2: &str) -> String {
-    assert!(hex1.len() == hex2.len());
-    let bytes1: Vec<u8> = hex_as_bytes(hex1);
+/// assert!(cryptopals::encodings::xor_hexes("02g", "bd1").is_err()); // invalid hex digit
+/// ```
+pub fn xor_hexes(hex1: &str, hex2: &str) -> Result<String> {
+    check_equal_lengths(hex1.len(), hex2.len())?;
+    let bytes1: Vec<u8> = hex_as_bytes(hex1)?;
     debug!("Xoring hex {:?} ", bytes1);
-    let bytes2: Vec<u8> = hex_as_bytes(hex2);
+    let bytes2: Vec<u8> = hex_as_bytes(hex2)?;
     debug!("with hex {:?} ", bytes2);
     let result = hexaa::xor_bytes(&bytes1, &bytes2);
-    bytes_to_hex(&result)
+    Ok(bytes_to_hex(&result))
 }
 
 /// Convert a vector of bytes to a hexadecimal ASCII string representation
-pub fn bytes_to_hex(bytes: &Vec<u8>) -> String {
-    let ascii = bytes
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes
         .iter()
-        .flat_map(|item| byte_as_hex_ascii(item))
-        .collect();
-    String::from_utf8(ascii).unwrap()
+        .flat_map(byte_as_hex_ascii)
+        .map(char::from)
+        .collect()
 }
 
 /// Converts a string representation (ASCII) of a hexadecimal number
@@ -133,24 +150,22 @@ pub fn bytes_to_hex(bytes: &Vec<u8>) -> String {
 /// The 0s are padded to the right and appear as '=' in final representation.
 /// ```
 /// let h = "a1"; // 1 byte provided; pads by 2 '=' char
-/// assert_eq!(cryptopals::encodings::hex_to_b64(h), "oQ==".to_owned());
+/// assert_eq!(cryptopals::encodings::hex_to_b64(h), Ok("oQ==".to_owned()));
 /// ```
 /// ```
 /// let h = "a110"; // 2 bytes provided; pads by 1 '=' char
-/// assert_eq!(cryptopals::encodings::hex_to_b64(h), "oRA=".to_owned());
+/// assert_eq!(cryptopals::encodings::hex_to_b64(h), Ok("oRA=".to_owned()));
 /// ```
 /// ```
 /// let h = "a11012"; // 3 bytes provided; no padding needed
-/// assert_eq!(cryptopals::encodings::hex_to_b64(h), "oRAS".to_owned());
+/// assert_eq!(cryptopals::encodings::hex_to_b64(h), Ok("oRAS".to_owned()));
+/// ```
 /// ```
-/// ```should_panic
 /// let h = "a11"; // incomplete number of bytes
-/// cryptopals::encodings::hex_to_b64(h);
+/// assert!(cryptopals::encodings::hex_to_b64(h).is_err());
 /// ```
-pub fn hex_to_b64(hex: &str) -> String {
-    assert!(hex.len() & 1 == 0);
-
-    bytes_to_b64(hex_as_bytes(hex))
+pub fn hex_to_b64(hex: &str) -> Result<String> {
+    Ok(bytes_to_b64(hex_as_bytes(hex)?))
 }
 
 /// Converts an ASCII representation of a hex string,
@@ -159,20 +174,18 @@ pub fn hex_to_b64(hex: &str) -> String {
 /// ```
 /// assert_eq!(
 ///     cryptopals::encodings::hex_as_bytes("ad1f"),
-///     vec![0b_1010_1101, 0b_0001_1111]);
+///     Ok(vec![0b_1010_1101, 0b_0001_1111]));
 /// ```
-/// ```should_panic
-/// assert_eq!(
-///     cryptopals::encodings::hex_as_bytes("ad1d1"),
-///     vec![0b_1010_1101, 0b_0001_1111]);
 /// ```
-pub fn hex_as_bytes(hex: &str) -> Vec<u8> {
-    assert_eq!(
-        hex.len() & 1,
-        0,
-        "Invalid hex string `{}`: Even number of digits expected.",
-        hex
-    );
+/// assert!(cryptopals::encodings::hex_as_bytes("ad1d1").is_err());
+/// ```
+pub fn hex_as_bytes(hex: &str) -> Result<Vec<u8>> {
+    if hex.len() & 1 != 0 {
+        return Err(Error::Decoding(format!(
+            "Invalid hex string `{}`: Even number of digits expected.",
+            hex
+        )));
+    }
     hex.as_bytes()
         .chunks(2)
         .map(|byte| hex_ascii_to_byte(byte[0], byte[1]))
@@ -182,26 +195,21 @@ pub fn hex_as_bytes(hex: &str) -> Vec<u8> {
 /// Converts an ASCII representation of 2 hexadecimal digits
 /// into binary equivalent (1 byte).
 /// Allowed hexadecimal digits : [0-9a-f]
-/// Panics if input does not correspond to the ASCII values of above characters
-fn hex_ascii_to_byte(s: u8, t: u8) -> u8 {
-    (if s >= 48 && s <= 57 {
+/// Fails if input does not correspond to the ASCII values of above characters
+fn hex_ascii_to_byte(s: u8, t: u8) -> Result<u8> {
+    Ok((hex_ascii_to_digit(s)? << 4) + hex_ascii_to_digit(t)?)
+}
+
+fn hex_ascii_to_digit(s: u8) -> Result<u8> {
+    match s {
         // [0-9]
-        s - 48
-    } else if s >= 97 && s <= 102 {
+        b'0'..=b'9' => Ok(s - 48),
         // [a-f]
-        s - 97 + 10
-    } else {
-        panic!("ASCII for hex should be a letter [a-f], or a digit [0-9].")
-    } << 4)
-        + (if t >= 48 && t <= 57 {
-            // [0-9]
-            t - 48
-        } else if t >= 97 && t <= 102 {
-            // [a-f]
-            t - 97 + 10
-        } else {
-            panic!("ASCII for hex should be a letter [a-f], or a digit [0-9].")
-        })
+        b'a'..=b'f' => Ok(s - 97 + 10),
+        _ => Err(Error::Decoding(
+            "ASCII for hex should be a letter [a-f], or a digit [0-9].".to_owned(),
+        )),
+    }
 }
 
 fn byte_as_hex_ascii(h: &u8) -> Vec<u8> {
@@ -229,61 +237,81 @@ fn byte_as_partial_hex_ascii(h: &u8) -> u8 {
 
 /// Converts an ASCII representation of a base-64 string,
 /// into a Vector of corresponding bytes.
-pub fn b64_as_bytes(b64: &str) -> Vec<u8> {
+/// Fails if the length is not a multiple of 4, on characters outside the base64 alphabet,
+/// and on misplaced padding.
+pub fn b64_as_bytes(b64: &str) -> Result<Vec<u8>> {
     let bytes = b64.as_bytes().to_vec();
 
-    assert_eq!(bytes.len() % 4, 0);
+    if !bytes.len().is_multiple_of(4) {
+        return Err(Error::Decoding(format!(
+            "Invalid base64 string `{}`: Multiple of 4 digits expected.",
+            b64
+        )));
+    }
 
+    let quads = bytes.len() / 4;
     bytes
         .chunks(4)
-        .flat_map(|c| b64_ascii_quad_to_hex_triad(c[0], c[1], c[2], c[3]))
-        .collect()
+        .enumerate()
+        .map(|(i, c)| {
+            if i + 1 < quads && c.contains(&b'=') {
+                Err(Error::Decoding(
+                    "Base64 padding is only allowed at the end.".to_owned(),
+                ))
+            } else {
+                b64_ascii_quad_to_hex_triad(c[0], c[1], c[2], c[3])
+            }
+        })
+        .collect::<Result<Vec<Vec<u8>>>>()
+        .map(|triads| triads.concat())
 }
 
-fn b64_ascii_quad_to_hex_triad(a: u8, b: u8, c: u8, d: u8) -> Vec<u8> {
-    let p = b64_ascii_to_byte(a).unwrap();
-    let q = b64_ascii_to_byte(b).unwrap();
-    let r = b64_ascii_to_byte(c);
-    let s = b64_ascii_to_byte(d);
+fn b64_ascii_quad_to_hex_triad(a: u8, b: u8, c: u8, d: u8) -> Result<Vec<u8>> {
+    let misplaced_padding = || Error::Decoding("Misplaced base64 padding.".to_owned());
+    let p = b64_ascii_to_byte(a)?.ok_or_else(misplaced_padding)?;
+    let q = b64_ascii_to_byte(b)?.ok_or_else(misplaced_padding)?;
+    let r = b64_ascii_to_byte(c)?;
+    let s = b64_ascii_to_byte(d)?;
 
     let mut bytes = vec![(p << 2) + (q >> 4)];
     if let Some(r) = r {
         bytes.push((q << 4) + (r >> 2))
     }
     if let Some(s) = s {
-        bytes.push((r.unwrap() << 6) + s)
+        bytes.push((r.ok_or_else(misplaced_padding)? << 6) + s)
     }
-    bytes
+    Ok(bytes)
 }
 
-fn bytes_to_b64(bytes: Vec<u8>) -> String {
-    let new_bytes = bytes
+/// Converts a vector of bytes to a base64 ASCII string representation
+/// ```
+/// assert_eq!(cryptopals::encodings::bytes_to_b64(vec![0xa1, 0x10]), "oRA=");
+/// ```
+pub fn bytes_to_b64(bytes: Vec<u8>) -> String {
+    let new_bytes: String = bytes
         .chunks(3)
         .flat_map(|v| match v.len() {
             1 => hex_triad_to_base64_quad(v[0], 0, 0).pad_b64(2),
             2 => hex_triad_to_base64_quad(v[0], v[1], 0).pad_b64(1),
-            3 => hex_triad_to_base64_quad(v[0], v[1], v[2]),
-            _ => panic!("Unexpected: Chunk should have a maximum length of 3."),
+            // chunks have a maximum length of 3
+            _ => hex_triad_to_base64_quad(v[0], v[1], v[2]),
         })
+        .map(char::from)
         .collect();
-    String::from_utf8(new_bytes).unwrap()
+    new_bytes
 }
 
-fn b64_ascii_to_byte(i: u8) -> Option<u8> {
-    if i >= 'A' as u8 && i <= 'Z' as u8 {
-        Some(i - 'A' as u8)
-    } else if i >= 'a' as u8 && i <= 'z' as u8 {
-        Some(i - 'a' as u8 + 26)
-    } else if i >= '0' as u8 && i <= '9' as u8 {
-        Some(i - '0' as u8 + 52)
-    } else if i == '+' as u8 {
-        Some(62)
-    } else if i == '/' as u8 {
-        Some(63)
-    } else if i == '=' as u8 {
-        None
-    } else {
-        panic!("Base64 characters should belong to the set [A-Za-z+/=].")
+fn b64_ascii_to_byte(i: u8) -> Result<Option<u8>> {
+    match i {
+        b'A'..=b'Z' => Ok(Some(i - b'A')),
+        b'a'..=b'z' => Ok(Some(i - b'a' + 26)),
+        b'0'..=b'9' => Ok(Some(i - b'0' + 52)),
+        b'+' => Ok(Some(62)),
+        b'/' => Ok(Some(63)),
+        b'=' => Ok(None),
+        _ => Err(Error::Decoding(
+            "Base64 characters should belong to the set [A-Za-z0-9+/=].".to_owned(),
+        )),
     }
 }
 
@@ -339,59 +367,73 @@ mod tests {
     #[test]
     fn hex_to_base64_should_work() {
         let h = "a11012";
-        assert_eq!(hex_to_b64(h), "oRAS");
+        assert_eq!(hex_to_b64(h).unwrap(), "oRAS");
     }
 
     #[test]
     fn hex_to_base64_should_pad_with_one_additional_zero() {
         let h = "a110";
-        assert_eq!(hex_to_b64(h), "oRA=");
+        assert_eq!(hex_to_b64(h).unwrap(), "oRA=");
     }
 
     #[test]
     fn hex_to_base64_should_pad_with_one_additional_zero_for_longer_hex() {
         let h = "f10a11bdef";
-        assert_eq!(hex_to_b64(h), "8QoRve8=");
+        assert_eq!(hex_to_b64(h).unwrap(), "8QoRve8=");
     }
 
     #[test]
     fn hex_to_base64_should_pad_with_two_additional_zeroes() {
         let h = "1f";
-        assert_eq!(hex_to_b64(h), "Hw==");
+        assert_eq!(hex_to_b64(h).unwrap(), "Hw==");
     }
 
     #[test]
-    #[should_panic]
-    fn b64_as_bytes_should_panic_if_length_is_not_a_multiple_of_four() {
-        b64_as_bytes("8QoRve8");
+    fn b64_as_bytes_should_fail_if_length_is_not_a_multiple_of_four() {
+        assert!(matches!(b64_as_bytes("8QoRve8"), Err(Error::Decoding(_))));
+    }
+
+    #[test]
+    fn b64_as_bytes_should_fail_for_invalid_characters_and_padding() {
+        assert!(b64_as_bytes("8Qo-").is_err());
+        assert!(b64_as_bytes("8Q==8Qo=").is_err());
+        assert!(b64_as_bytes("8Q=o").is_err());
+        assert!(b64_as_bytes("====").is_err());
     }
 
     #[test]
     fn b64_as_bytes_should_convert_b64_strings_to_bytes() {
-        assert_eq!(b64_as_bytes("oRAS"), vec![0xa1, 0x10, 0x12]);
-        assert_eq!(b64_as_bytes("oRA="), vec![0xa1, 0x10]);
-        assert_eq!(b64_as_bytes("8QoRve8="), vec![0xf1, 0x0a, 0x11, 0xbd, 0xef]);
-        assert_eq!(b64_as_bytes("Hw=="), vec![0x1f]);
+        assert_eq!(b64_as_bytes("oRAS"), Ok(vec![0xa1, 0x10, 0x12]));
+        assert_eq!(b64_as_bytes("oRA="), Ok(vec![0xa1, 0x10]));
+        assert_eq!(
+            b64_as_bytes("8QoRve8="),
+            Ok(vec![0xf1, 0x0a, 0x11, 0xbd, 0xef])
+        );
+        assert_eq!(b64_as_bytes("Hw=="), Ok(vec![0x1f]));
     }
 
     #[test]
     fn ascii_to_hex_happy_path() {
-        assert_eq!(hex_ascii_to_byte('9' as u8, 'a' as u8), 0b10011010);
-        assert_eq!(hex_ascii_to_byte('a' as u8, 'f' as u8), 0b10101111);
-        assert_eq!(hex_ascii_to_byte('f' as u8, '0' as u8), 0b11110000);
-        assert_eq!(hex_ascii_to_byte('0' as u8, '9' as u8), 0b00001001);
+        assert_eq!(hex_ascii_to_byte(b'9', b'a'), Ok(0b10011010));
+        assert_eq!(hex_ascii_to_byte(b'a', b'f'), Ok(0b10101111));
+        assert_eq!(hex_ascii_to_byte(b'f', b'0'), Ok(0b11110000));
+        assert_eq!(hex_ascii_to_byte(b'0', b'9'), Ok(0b00001001));
     }
 
     #[test]
-    #[should_panic]
-    fn ascii_to_hex_should_panic_for_uppercase_letters() {
-        hex_ascii_to_byte('0' as u8, 'A' as u8);
+    fn ascii_to_hex_should_fail_for_uppercase_letters() {
+        assert!(matches!(
+            hex_ascii_to_byte(b'0', b'A'),
+            Err(Error::Decoding(_))
+        ));
     }
 
     #[test]
-    #[should_panic]
-    fn ascii_to_hex_should_panic_for_out_of_bounds_letters() {
-        hex_ascii_to_byte('0' as u8, 'z' as u8);
+    fn ascii_to_hex_should_fail_for_out_of_bounds_letters() {
+        assert!(matches!(
+            hex_ascii_to_byte(b'0', b'z'),
+            Err(Error::Decoding(_))
+        ));
     }
 
     #[test]
@@ -411,13 +453,24 @@ mod tests {
 
     #[test]
     fn hamming_distance_should_calculate() {
-        assert_eq!(hamming_distance("this is a test", "wokka wokka!!!"), 37);
+        assert_eq!(hamming_distance("this is a test", "wokka wokka!!!"), Ok(37));
     }
 
     #[test]
-    #[should_panic]
-    fn hamming_distance_should_panic() {
-        hamming_distance("this is a test", "wokka wokka");
+    fn bytes_hamming_distance_should_calculate() {
+        assert_eq!(bytes_hamming_distance(&[0x00, 0xff], &[0x01, 0x0f]), Ok(5));
+        assert!(bytes_hamming_distance(&[0x00], &[0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn hamming_distance_should_fail_for_different_lengths() {
+        assert_eq!(
+            hamming_distance("this is a test", "wokka wokka"),
+            Err(Error::LengthMismatch {
+                expected: 14,
+                actual: 11
+            })
+        );
     }
 
     #[test]
@@ -427,17 +480,17 @@ mod tests {
                 "this is a test".as_bytes().to_vec().as_ref(),
                 "wokka wokka!!!".as_bytes().to_vec().as_ref()
             ),
-            37.0 / 14.0
+            Ok(37.0 / 14.0)
         );
     }
 
     #[test]
-    #[should_panic]
-    fn normalized_hamming_distance_should_panic() {
-        normalized_hamming_distance(
+    fn normalized_hamming_distance_should_fail_for_different_lengths() {
+        assert!(normalized_hamming_distance(
             "this is a test".as_bytes().to_vec().as_ref(),
             "wokka wokka".as_bytes().to_vec().as_ref(),
-        );
+        )
+        .is_err());
     }
 
     #[test]
@@ -445,26 +498,37 @@ mod tests {
         let expected = normalized_hamming_distance(
             "th".as_bytes().to_vec().as_ref(),
             "is".as_bytes().to_vec().as_ref(),
-        ) + normalized_hamming_distance(
-            "th".as_bytes().to_vec().as_ref(),
-            " i".as_bytes().to_vec().as_ref(),
-        ) + normalized_hamming_distance(
-            "th".as_bytes().to_vec().as_ref(),
-            "s ".as_bytes().to_vec().as_ref(),
-        ) + normalized_hamming_distance(
-            "is".as_bytes().to_vec().as_ref(),
-            " i".as_bytes().to_vec().as_ref(),
-        ) + normalized_hamming_distance(
-            "is".as_bytes().to_vec().as_ref(),
-            "s ".as_bytes().to_vec().as_ref(),
-        ) + normalized_hamming_distance(
-            " i".as_bytes().to_vec().as_ref(),
-            "s ".as_bytes().to_vec().as_ref(),
-        );
+        )
+        .unwrap()
+            + normalized_hamming_distance(
+                "th".as_bytes().to_vec().as_ref(),
+                " i".as_bytes().to_vec().as_ref(),
+            )
+            .unwrap()
+            + normalized_hamming_distance(
+                "th".as_bytes().to_vec().as_ref(),
+                "s ".as_bytes().to_vec().as_ref(),
+            )
+            .unwrap()
+            + normalized_hamming_distance(
+                "is".as_bytes().to_vec().as_ref(),
+                " i".as_bytes().to_vec().as_ref(),
+            )
+            .unwrap()
+            + normalized_hamming_distance(
+                "is".as_bytes().to_vec().as_ref(),
+                "s ".as_bytes().to_vec().as_ref(),
+            )
+            .unwrap()
+            + normalized_hamming_distance(
+                " i".as_bytes().to_vec().as_ref(),
+                "s ".as_bytes().to_vec().as_ref(),
+            )
+            .unwrap();
         let expected = expected / 6.;
         assert_eq!(
             average_normalized_hamming_distance(2, "this is a test".as_bytes().to_vec().as_ref()),
-            expected
+            Ok(expected)
         );
     }
 
@@ -473,13 +537,19 @@ mod tests {
         let expected = normalized_hamming_distance(
             "this ".as_bytes().to_vec().as_ref(),
             "is a ".as_bytes().to_vec().as_ref(),
-        );
+        )
+        .unwrap();
         assert_eq!(
             average_normalized_hamming_distance(5, "this is a test".as_bytes().to_vec().as_ref()),
-            expected
+            Ok(expected)
         );
     }
 
+    #[test]
+    fn average_normalized_hamming_distance_should_fail_when_there_is_a_single_slice() {
+        assert!(average_normalized_hamming_distance(10, b"this is a test").is_err());
+    }
+
     #[test]
     fn combine_should_combine() {
         let x = vec![0, 5, 10, 15];
@@ -1,17 +1,19 @@
 use crate::encodings;
-use crate::hexaa;
+use crate::error::{Error, Result};
+use crate::hexaa::{self, LengthPolicy};
 
-pub fn repeated_key_xor_with_strings(hex: &str, key: &str) -> String {
+pub fn repeated_key_xor_with_strings(hex: &str, key: &str) -> Result<String> {
     let bytes: Vec<u8> = hex.as_bytes().to_vec();
     let key: Vec<u8> = key.as_bytes().to_vec();
-    let encrypted = repeated_key_xor(&bytes, &key);
-    encodings::bytes_to_hex(&encrypted)
+    let encrypted = repeated_key_xor(&bytes, &key)?;
+    Ok(encodings::bytes_to_hex(&encrypted))
 }
 
-pub fn repeated_key_xor(bytes: &Vec<u8>, key: &Vec<u8>) -> Vec<u8> {
-    let repeated_key = bytes
-        .chunks(key.len())
-        .flat_map(|chunk| chunk.iter().zip(key.clone()).map(|(_, key_byte)| key_byte))
-        .collect();
-    hexaa::xor_bytes(&bytes, &repeated_key)
+/// XORs the bytes with the key, repeated over the length of the bytes.
+/// Fails if the key is empty.
+pub fn repeated_key_xor(bytes: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if key.is_empty() {
+        return Err(Error::InvalidKey("key should not be empty".to_owned()));
+    }
+    hexaa::xor(bytes, key, LengthPolicy::Cycle)
 }
@@ -0,0 +1,65 @@
+use crate::encodings;
+use std::fmt;
+use std::io;
+
+/// Errors returned by the fallible functions of the crate.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// Input is not valid in the encoding it was expected in (e.g. hex, base64).
+    Decoding(String),
+    /// Inputs that should have been of a given length, or of equal lengths, are not.
+    LengthMismatch { expected: usize, actual: usize },
+    /// A key is not usable, e.g. an empty key.
+    InvalidKey(String),
+    /// Decrypted plaintext is not validly padded.
+    Padding,
+    /// Decrypted plaintext is not acceptable, e.g. not ASCII.
+    /// Carries the plaintext, as careless services report it.
+    InvalidPlaintext(Vec<u8>),
+    /// A message failed authentication.
+    Authentication,
+    /// A cryptanalytic attack could not reach a result.
+    Cryptanalysis(String),
+    /// An oracle refused a query, having answered as many as its budget allows.
+    BudgetExhausted { budget: u64 },
+    /// Reading or writing failed, e.g. talking to a server.
+    Io(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Decoding(message) => write!(f, "decoding failed: {}", message),
+            Error::LengthMismatch { expected, actual } => write!(
+                f,
+                "length mismatch: expected {} bytes, got {}",
+                expected, actual
+            ),
+            Error::InvalidKey(message) => write!(f, "invalid key: {}", message),
+            Error::Padding => write!(f, "invalid padding"),
+            Error::InvalidPlaintext(plaintext) => {
+                write!(
+                    f,
+                    "invalid plaintext: {}",
+                    encodings::bytes_to_hex(plaintext)
+                )
+            }
+            Error::Authentication => write!(f, "authentication failed"),
+            Error::Cryptanalysis(message) => write!(f, "cryptanalysis failed: {}", message),
+            Error::BudgetExhausted { budget } => {
+                write!(f, "query budget of {} exhausted", budget)
+            }
+            Error::Io(message) => write!(f, "i/o failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error.to_string())
+    }
+}
@@ -0,0 +1,208 @@
+//! Statistical break of CTR mode ciphertexts encrypted with a fixed nonce.
+//!
+//! With the nonce fixed, every message is XOR'ed with the same keystream.
+//! Truncated to the length of the shortest, the ciphertexts concatenated are
+//! a repeating-key XOR ciphertext with a key of that length, which the column
+//! solver of `decrypt` breaks. Past the shortest ciphertext, fewer and fewer
+//! ciphertexts share a column, so every column keeps a pool of candidate keystream
+//! bytes, and the best guess can be corrected by hand from the candidates or from
+//! a guess of the plaintext.
+
+use crate::decrypt;
+use crate::error::{Error, Result};
+use crate::many_time_pad::ManyTimePad;
+use crate::scoring::Scorer;
+
+/// The state of the attack: the keystream recovered so far,
+/// and the candidates for every keystream byte.
+pub struct FixedNonceCtr {
+    pad: ManyTimePad,
+    common_length: usize,
+    pools: Vec<Vec<(u8, f32)>>,
+}
+
+impl FixedNonceCtr {
+    /// Fails if there are no ciphertexts, or if one of them is empty.
+    pub fn new(ciphertexts: Vec<Vec<u8>>) -> Result<Self> {
+        let common_length = ciphertexts
+            .iter()
+            .map(|c| c.len())
+            .min()
+            .filter(|length| *length > 0)
+            .ok_or_else(|| Error::Cryptanalysis("no ciphertexts to break".to_owned()))?;
+        let pad = ManyTimePad::new(ciphertexts);
+        let pools = vec![vec![]; pad.keystream().len()];
+        Ok(FixedNonceCtr {
+            pad,
+            common_length,
+            pools,
+        })
+    }
+
+    /// Length of the shortest ciphertext.
+    pub fn common_length(&self) -> usize {
+        self.common_length
+    }
+
+    /// The ciphertexts truncated to the common length, concatenated.
+    pub fn truncated(&self) -> Vec<u8> {
+        self.pad
+            .ciphertexts()
+            .iter()
+            .flat_map(|c| c[..self.common_length].iter().copied())
+            .collect()
+    }
+
+    /// Recovers the first common length bytes of the keystream, breaking the truncated
+    /// ciphertexts as repeating-key XOR, and guesses the rest of the keystream
+    /// from candidate pools of at most pool_size bytes per column.
+    pub fn solve<S: Scorer>(&mut self, scorer: &S, pool_size: usize) {
+        let key = decrypt::solve_repeating_key_xor(&self.truncated(), self.common_length, scorer);
+        key.iter()
+            .enumerate()
+            .for_each(|(i, byte)| self.pad.set_keystream_byte(i, *byte));
+        self.extend(scorer, pool_size);
+    }
+
+    /// Fills the candidate pools of all the columns, and guesses every keystream byte
+    /// not yet known past the common length as the best candidate of its column.
+    pub fn extend<S: Scorer>(&mut self, scorer: &S, pool_size: usize) {
+        for i in 0..self.pools.len() {
+            let mut candidates = self.pad.column_candidates(i, scorer);
+            candidates.truncate(pool_size);
+            if i >= self.common_length && self.pad.keystream()[i].is_none() {
+                if let Some((byte, _)) = candidates.first() {
+                    self.pad.set_keystream_byte(i, *byte);
+                }
+            }
+            self.pools[i] = candidates;
+        }
+    }
+
+    /// Candidate keystream bytes for column i, with their scores, best first.
+    pub fn pool(&self, i: usize) -> &[(u8, f32)] {
+        &self.pools[i]
+    }
+
+    /// Number of ciphertexts with a byte in column i, i.e. how much evidence its pool rests on.
+    pub fn column_depth(&self, i: usize) -> usize {
+        self.pad.column(i).len()
+    }
+
+    /// Takes the candidate of the given rank in the pool of column i as its keystream byte.
+    pub fn choose(&mut self, i: usize, rank: usize) -> Result<()> {
+        let (byte, _) = *self
+            .pools
+            .get(i)
+            .and_then(|pool| pool.get(rank))
+            .ok_or_else(|| {
+                Error::Cryptanalysis(format!("column {} has no candidate of rank {}", i, rank))
+            })?;
+        self.pad.set_keystream_byte(i, byte);
+        Ok(())
+    }
+
+    /// Sets a keystream byte by hand.
+    pub fn set_keystream_byte(&mut self, i: usize, byte: u8) {
+        self.pad.set_keystream_byte(i, byte);
+    }
+
+    /// Corrects the keystream with a guess that ciphertext number `ciphertext`
+    /// decrypts to plaintext at offset.
+    pub fn guess(&mut self, ciphertext: usize, offset: usize, plaintext: &[u8]) {
+        self.pad.guess(ciphertext, offset, plaintext);
+    }
+
+    pub fn keystream(&self) -> &[Option<u8>] {
+        self.pad.keystream()
+    }
+
+    /// Decrypts every ciphertext with the keystream recovered so far.
+    pub fn plaintexts(&self) -> Vec<Vec<Option<u8>>> {
+        self.pad.plaintexts()
+    }
+
+    /// Renders the plaintexts for display, as `ManyTimePad::render` does.
+    pub fn render(&self, unknown: char) -> Vec<String> {
+        self.pad.render(unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes128::modes;
+    use crate::scoring::LetterFrequency;
+
+    const MESSAGES: [&str; 12] = [
+        "I have met them at close of day",
+        "Coming with vivid faces",
+        "From counter or desk among grey",
+        "Eighteenth-century houses.",
+        "I have passed with a nod of the head",
+        "Or polite meaningless words,",
+        "Or have lingered awhile and said",
+        "Polite meaningless words,",
+        "And thought before I had done",
+        "Of a mocking tale or a gibe",
+        "To please a companion",
+        "Around the fire at the club,",
+    ];
+
+    fn encrypted() -> Vec<Vec<u8>> {
+        MESSAGES
+            .iter()
+            .map(|m| modes::ctr(m.as_bytes(), 0x5eed, 0))
+            .collect()
+    }
+
+    fn correct(recovered: &str, message: &str, length: usize) -> usize {
+        recovered
+            .bytes()
+            .zip(message.bytes())
+            .take(length)
+            .filter(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count()
+    }
+
+    #[test]
+    fn solve_should_recover_most_of_the_common_length() {
+        let mut attack = FixedNonceCtr::new(encrypted()).unwrap();
+        assert_eq!(attack.common_length(), 21);
+        attack.solve(&LetterFrequency::new(), 4);
+
+        let recovered = attack.render('?');
+        let total: usize = (0..MESSAGES.len())
+            .map(|i| correct(&recovered[i], MESSAGES[i], 21))
+            .sum();
+        assert!(total >= 21 * MESSAGES.len() * 3 / 4, "{:?}", recovered);
+    }
+
+    #[test]
+    fn solve_should_keep_pools_past_the_common_length() {
+        let mut attack = FixedNonceCtr::new(encrypted()).unwrap();
+        attack.solve(&LetterFrequency::new(), 4);
+        assert_eq!(attack.keystream().len(), 36);
+        assert!(attack.keystream().iter().all(|k| k.is_some()));
+        assert_eq!(attack.column_depth(35), 1);
+        assert!((0..36).all(|i| attack.pool(i).len() == 4));
+        assert!(attack.choose(35, 3).is_ok());
+        assert!(attack.choose(35, 4).is_err());
+    }
+
+    #[test]
+    fn guess_should_correct_the_tail_of_the_longest_plaintext() {
+        let mut attack = FixedNonceCtr::new(encrypted()).unwrap();
+        attack.solve(&LetterFrequency::new(), 4);
+        attack.guess(4, 21, b"nod of the head");
+        let recovered = attack.render('?');
+        assert!(recovered[4].ends_with("a nod of the head"));
+        assert_eq!(&recovered[6][21..], "le and said");
+    }
+
+    #[test]
+    fn new_should_reject_empty_sets() {
+        assert!(FixedNonceCtr::new(vec![]).is_err());
+        assert!(FixedNonceCtr::new(vec![vec![1], vec![]]).is_err());
+    }
+}
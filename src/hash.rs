@@ -0,0 +1,29 @@
+//! The interface shared by the hash functions of the crate.
+
+/// A hash function, computed incrementally: created with new, fed with update,
+/// and finished with finalize.
+/// ```
+/// use cryptopals::{encodings, hash::Hash, sha1::Sha1};
+/// assert_eq!(
+///     encodings::bytes_to_hex(&Sha1::digest(b"abc")),
+///     "a9993e364706816aba3e25717850c26c9cd0d89d");
+/// ```
+pub trait Hash: Clone {
+    /// Number of bytes the hash processes at a time, as used by HMAC.
+    const BLOCK_SIZE: usize;
+    const DIGEST_SIZE: usize;
+
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    /// Pads the message and returns the digest.
+    fn finalize(self) -> Vec<u8>;
+
+    /// The digest of the data.
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hash = Self::new();
+        hash.update(data);
+        hash.finalize()
+    }
+}
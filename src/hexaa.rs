@@ -1,4 +1,18 @@
+use crate::error::{Error, Result};
 use log::debug;
+use std::convert::TryInto;
+
+/// What to do when the inputs of a XOR are of different lengths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LengthPolicy {
+    /// XOR only as many bytes as the shorter input has.
+    Truncate,
+    /// Fail unless the inputs are of equal lengths.
+    Strict,
+    /// Repeat the second input (e.g. a key) over the length of the first.
+    /// Fails if the second input is empty, unless the first is empty too.
+    Cycle,
+}
 
 /// XORs a vector of bytes with repeated instances of another byte.
 /// ```
@@ -8,15 +22,24 @@ use log::debug;
 ///       0x1d),
 ///     vec![0xab ^ 0x1d, 0xd1 ^ 0x1d, 0x07 ^ 0x1d, 0x92 ^ 0x1d]);
 /// ```
-pub fn repeated_byte_xor(bytes1: &Vec<u8>, charr: u8) -> Vec<u8> {
-    debug!("Xoring hex {:?} ", bytes1);
-    // repeat `charr` the length of `hex1` times
-    let bytes2: Vec<u8> = bytes1.iter().map(|_| charr).collect();
-    debug!("with hex {:?} ", bytes2);
-    xor_bytes(&bytes1, &bytes2)
+pub fn repeated_byte_xor<B: AsRef<[u8]>>(bytes: B, byte: u8) -> Vec<u8> {
+    let mut xored = bytes.as_ref().to_vec();
+    xor_byte_in_place(&mut xored, byte);
+    xored
+}
+
+/// XORs every byte of the slice, in place, with the byte.
+pub fn xor_byte_in_place(bytes: &mut [u8], byte: u8) {
+    let word = u64::from_ne_bytes([byte; 8]);
+    let mut words = bytes.chunks_exact_mut(8);
+    for chunk in &mut words {
+        let xored = u64::from_ne_bytes((*chunk).try_into().unwrap()) ^ word;
+        chunk.copy_from_slice(&xored.to_ne_bytes());
+    }
+    words.into_remainder().iter_mut().for_each(|b| *b ^= byte);
 }
 
-/// XORs the bytes in the input vectors sequentially.
+/// XORs the bytes in the inputs sequentially, up to the length of the shorter one.
 /// ```
 /// assert_eq!(
 ///     cryptopals::hexaa::xor_bytes(
@@ -24,10 +47,100 @@ pub fn repeated_byte_xor(bytes1: &Vec<u8>, charr: u8) -> Vec<u8> {
 ///         &vec![31, 63, 127, 255]),
 ///     vec![1^31, 3^63, 7^127, 15^255]);
 /// ```
-pub fn xor_bytes(b1: &Vec<u8>, b2: &Vec<u8>) -> Vec<u8> {
+pub fn xor_bytes<A: AsRef<[u8]>, B: AsRef<[u8]>>(b1: A, b2: B) -> Vec<u8> {
+    let (b1, b2) = (b1.as_ref(), b2.as_ref());
     debug!("xoring {:?}", b1);
     debug!("with   {:?}", b2);
-    b1.iter().zip(b2.iter()).map(|(b1, b2)| b1 ^ b2).collect()
+    let mut xored = b1[..b1.len().min(b2.len())].to_vec();
+    xor_into(&mut xored, b2);
+    xored
+}
+
+/// XORs the target, in place, with the other input, up to the length of the shorter one.
+pub fn xor_bytes_in_place<B: AsRef<[u8]>>(target: &mut [u8], other: B) {
+    let other = other.as_ref();
+    let length = target.len().min(other.len());
+    xor_into(&mut target[..length], other);
+}
+
+/// XORs the inputs, handling a difference in their lengths as the policy says.
+/// ```
+/// use cryptopals::hexaa::{xor, LengthPolicy};
+/// assert_eq!(xor(b"abc", [1, 2], LengthPolicy::Truncate), Ok(vec![b'a' ^ 1, b'b' ^ 2]));
+/// assert_eq!(xor(b"abc", [1, 2], LengthPolicy::Cycle), Ok(vec![b'a' ^ 1, b'b' ^ 2, b'c' ^ 1]));
+/// assert!(xor(b"abc", [1, 2], LengthPolicy::Strict).is_err());
+/// ```
+pub fn xor<A: AsRef<[u8]>, B: AsRef<[u8]>>(a: A, b: B, policy: LengthPolicy) -> Result<Vec<u8>> {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let mut xored = match policy {
+        LengthPolicy::Truncate => a[..a.len().min(b.len())].to_vec(),
+        _ => a.to_vec(),
+    };
+    xor_in_place(&mut xored, b, policy)?;
+    Ok(xored)
+}
+
+/// XORs the target, in place, with the other input,
+/// handling a difference in their lengths as the policy says.
+/// With `LengthPolicy::Truncate` only the start of the target, as long as the other input,
+/// is changed when the other input is shorter.
+/// ```
+/// use cryptopals::hexaa::{xor_in_place, LengthPolicy};
+/// let mut bytes = [0u8; 5];
+/// xor_in_place(&mut bytes, b"ab", LengthPolicy::Cycle).unwrap();
+/// assert_eq!(&bytes, b"ababa");
+/// ```
+pub fn xor_in_place<B: AsRef<[u8]>>(
+    target: &mut [u8],
+    other: B,
+    policy: LengthPolicy,
+) -> Result<()> {
+    let other = other.as_ref();
+    match policy {
+        LengthPolicy::Truncate => xor_bytes_in_place(target, other),
+        LengthPolicy::Strict => {
+            if target.len() != other.len() {
+                return Err(Error::LengthMismatch {
+                    expected: target.len(),
+                    actual: other.len(),
+                });
+            }
+            xor_into(target, other);
+        }
+        LengthPolicy::Cycle => {
+            if other.is_empty() {
+                return if target.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Error::InvalidKey("key should not be empty".to_owned()))
+                };
+            }
+            // repeat short keys into a longer pattern, so that whole words get XOR'ed
+            let pattern = other.repeat(64usize.div_ceil(other.len()));
+            target
+                .chunks_mut(pattern.len())
+                .for_each(|chunk| xor_into(chunk, &pattern));
+        }
+    }
+    Ok(())
+}
+
+/// XORs target with the start of source, eight bytes at a time.
+/// Source should be at least as long as target.
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    let source = &source[..target.len()];
+    let mut words = target.chunks_exact_mut(8);
+    let mut source_words = source.chunks_exact(8);
+    for (chunk, source_chunk) in (&mut words).zip(&mut source_words) {
+        let xored = u64::from_ne_bytes((*chunk).try_into().unwrap())
+            ^ u64::from_ne_bytes(source_chunk.try_into().unwrap());
+        chunk.copy_from_slice(&xored.to_ne_bytes());
+    }
+    words
+        .into_remainder()
+        .iter_mut()
+        .zip(source_words.remainder())
+        .for_each(|(b, s)| *b ^= s);
 }
 
 /// Splits a byte (representing 2 hex digits) into two,
@@ -50,3 +163,74 @@ pub fn count_set_bits(byte: u8) -> u32 {
         1 + count_set_bits(byte & (byte - 1))
     }
 }
+
+/// Packs up to 16 bytes, most significant first, into a u128.
+/// ```
+/// assert_eq!(cryptopals::hexaa::bytes_to_u128(&[0x01, 0x02]), 0x0102);
+/// ```
+pub fn bytes_to_u128(bytes: &[u8]) -> u128 {
+    bytes
+        .iter()
+        .fold(0, |acc, byte| (acc << 8) | (*byte as u128))
+}
+
+/// Unpacks a u128 into its 16 bytes, most significant first.
+/// ```
+/// let bytes = cryptopals::hexaa::u128_to_bytes(0x0102);
+/// assert_eq!(bytes[14..], [0x01, 0x02]);
+/// ```
+pub fn u128_to_bytes(block: u128) -> Vec<u8> {
+    block.to_be_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytewise(a: &[u8], b: &[u8]) -> Vec<u8> {
+        a.iter().zip(b.iter().cycle()).map(|(a, b)| a ^ b).collect()
+    }
+
+    #[test]
+    fn xor_should_match_bytewise_xor_around_word_boundaries() {
+        let a: Vec<u8> = (0..41u8).map(|i| i.wrapping_mul(37)).collect();
+        let b: Vec<u8> = (0..41u8).map(|i| i.wrapping_mul(91) ^ 0x5a).collect();
+        for length in 0..a.len() {
+            assert_eq!(
+                xor(&a[..length], &b[..length], LengthPolicy::Strict),
+                Ok(bytewise(&a[..length], &b[..length]))
+            );
+            assert_eq!(
+                repeated_byte_xor(&a[..length], 0xc3),
+                bytewise(&a[..length], &[0xc3])
+            );
+        }
+        for key_length in 1..12 {
+            assert_eq!(
+                xor(&a, &b[..key_length], LengthPolicy::Cycle),
+                Ok(bytewise(&a, &b[..key_length]))
+            );
+        }
+    }
+
+    #[test]
+    fn xor_should_apply_the_length_policy() {
+        assert_eq!(xor([1, 2, 3], [1], LengthPolicy::Truncate), Ok(vec![0]));
+        assert_eq!(
+            xor([1, 2, 3], [1], LengthPolicy::Strict),
+            Err(Error::LengthMismatch {
+                expected: 3,
+                actual: 1
+            })
+        );
+        assert!(xor([1, 2, 3], [], LengthPolicy::Cycle).is_err());
+        assert_eq!(xor([], [], LengthPolicy::Cycle), Ok(vec![]));
+    }
+
+    #[test]
+    fn xor_in_place_should_only_touch_the_overlap_when_truncating() {
+        let mut bytes = [1, 2, 3, 4];
+        xor_in_place(&mut bytes, [1, 2], LengthPolicy::Truncate).unwrap();
+        assert_eq!(bytes, [0, 0, 3, 4]);
+    }
+}
@@ -0,0 +1,227 @@
+//! HMAC (RFC 2104) over any hash of the crate:
+//! H((key ^ opad) || H((key ^ ipad) || message)).
+//!
+//! Unlike a secret-prefix MAC, the outer hash keeps the inner digest from being extended,
+//! and since it only uses the hash as a black box it works alike for Merkle–Damgård
+//! hashes and SHA-3.
+
+use crate::error::{Error, Result};
+use crate::hash::Hash;
+
+/// The shortest truncated MAC which RFC 2104 allows, in bytes.
+pub const MIN_TRUNCATED_LENGTH: usize = 10;
+
+/// An HMAC computation in progress, fed with update.
+/// ```
+/// use cryptopals::{encodings, hmac::Hmac, sha2::Sha256};
+/// let mut hmac = Hmac::<Sha256>::new(b"key");
+/// hmac.update(b"The quick brown fox ");
+/// hmac.update(b"jumps over the lazy dog");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&hmac.finalize()),
+///     "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Hmac<H> {
+    inner: H,
+    /// The outer hash, which has already hashed key ^ opad.
+    outer: H,
+}
+
+impl<H: Hash> Hmac<H> {
+    /// Keys longer than a block are hashed first.
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = if key.len() > H::BLOCK_SIZE {
+            H::digest(key)
+        } else {
+            key.to_vec()
+        };
+        block.resize(H::BLOCK_SIZE, 0);
+
+        let mut inner = H::new();
+        inner.update(&block.iter().map(|byte| byte ^ 0x36).collect::<Vec<u8>>());
+        let mut outer = H::new();
+        outer.update(&block.iter().map(|byte| byte ^ 0x5c).collect::<Vec<u8>>());
+        Hmac { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// The MAC of the message.
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    /// Whether mac is the MAC of the message, compared in constant time.
+    pub fn verify(self, mac: &[u8]) -> bool {
+        constant_time_eq(&self.finalize(), mac)
+    }
+
+    /// Whether mac is the MAC of the message truncated to length bytes, compared in
+    /// constant time. The length is the verifier's choice, and RFC 2104 requires it
+    /// to be at least half the digest, and at least 80 bits.
+    pub fn verify_truncated(self, mac: &[u8], length: usize) -> Result<bool> {
+        let shortest = H::DIGEST_SIZE.div_ceil(2).max(MIN_TRUNCATED_LENGTH);
+        if length < shortest || length > H::DIGEST_SIZE {
+            return Err(Error::LengthMismatch {
+                expected: length.clamp(shortest, H::DIGEST_SIZE),
+                actual: length,
+            });
+        }
+        Ok(constant_time_eq(&self.finalize()[..length], mac))
+    }
+}
+
+/// The HMAC of the message under the key.
+pub fn hmac<H: Hash>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut hmac = Hmac::<H>::new(key);
+    hmac.update(message);
+    hmac.finalize()
+}
+
+/// Whether a and b are equal, in a time which depends on their lengths only.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(difference) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+    use crate::md5::Md5;
+    use crate::sha1::Sha1;
+    use crate::sha2::{Sha224, Sha256, Sha384, Sha512};
+    use crate::sha3::Sha3_256;
+
+    const JEFE: (&[u8], &[u8]) = (b"Jefe", b"what do ya want for nothing?");
+
+    fn hex<H: Hash>(key: &[u8], message: &[u8]) -> String {
+        encodings::bytes_to_hex(&hmac::<H>(key, message))
+    }
+
+    #[test]
+    fn hmac_should_match_rfc_2202_vectors() {
+        let (key, message) = JEFE;
+        assert_eq!(hex::<Md5>(key, message), "750c783e6ab0b503eaa86e310a5db738");
+        assert_eq!(
+            hex::<Sha1>(key, message),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        );
+        assert_eq!(
+            hex::<Md5>(&[0x0b; 16], b"Hi There"),
+            "9294727a3638bb1c13f48ef8158bfc9d"
+        );
+        assert_eq!(
+            hex::<Sha1>(&[0x0b; 20], b"Hi There"),
+            "b617318655057264e28bc0b6fb378c8ef146be00"
+        );
+        // keys longer than a block
+        let message = b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data";
+        assert_eq!(
+            hex::<Md5>(&[0xaa; 80], message),
+            "6f630fad67cda0ee1fb1f562db3aa53e"
+        );
+        assert_eq!(
+            hex::<Sha1>(&[0xaa; 80], message),
+            "e8e99d0f45237d786d6bbaa7965c7808bbff1a91"
+        );
+    }
+
+    #[test]
+    fn hmac_should_match_rfc_4231_vectors() {
+        let long_key = [0xaa; 131];
+        let vectors: [(&[u8], &[u8], [&str; 4]); 4] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                [
+                    "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+                    "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                    "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+                    "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+                ],
+            ),
+            (
+                JEFE.0,
+                JEFE.1,
+                [
+                    "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+                    "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                    "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+                    "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+                ],
+            ),
+            (
+                &long_key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                [
+                    "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+                    "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                    "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+                    "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+                ],
+            ),
+            (
+                &long_key,
+                b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.",
+                [
+                    "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+                    "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+                    "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5a678cc31e799176d3860e6110c46523e",
+                    "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+                ],
+            ),
+        ];
+        for (key, message, macs) in vectors {
+            assert_eq!(hex::<Sha224>(key, message), macs[0]);
+            assert_eq!(hex::<Sha256>(key, message), macs[1]);
+            assert_eq!(hex::<Sha384>(key, message), macs[2]);
+            assert_eq!(hex::<Sha512>(key, message), macs[3]);
+        }
+        // test case 5, truncated to 128 bits
+        let mut hmac = Hmac::<Sha256>::new(&[0x0c; 20]);
+        hmac.update(b"Test With Truncation");
+        let mac = encodings::hex_as_bytes("a3b6167473100ee06e0c796c2955552b").unwrap();
+        assert_eq!(hmac.clone().verify_truncated(&mac, 16), Ok(true));
+        assert!(!hmac.verify(&mac));
+    }
+
+    #[test]
+    fn hmac_should_work_over_sha3() {
+        let (key, message) = JEFE;
+        assert_eq!(
+            hex::<Sha3_256>(key, message),
+            "c7d4072e788877ae3596bbb0da73b887c9171f93095b294ae857fbe2645e1ba5"
+        );
+    }
+
+    #[test]
+    fn verify_should_reject_altered_and_short_macs() {
+        let data: Vec<u8> = (0..=255).cycle().take(300).collect();
+        let mut streamed = Hmac::<Sha1>::new(b"key");
+        data.chunks(7).for_each(|chunk| streamed.update(chunk));
+        let mac = hmac::<Sha1>(b"key", &data);
+        assert!(streamed.clone().verify(&mac));
+
+        let mut altered = mac.clone();
+        altered[19] ^= 1;
+        assert!(!streamed.clone().verify(&altered));
+        assert!(!streamed.clone().verify(&mac[..10]));
+        assert!(!streamed.clone().verify(&[mac.clone(), vec![0]].concat()));
+        assert_eq!(streamed.clone().verify_truncated(&mac[..12], 12), Ok(true));
+        // the verifier fixes the length, not whoever sends the MAC
+        assert_eq!(streamed.clone().verify_truncated(&mac[..10], 12), Ok(false));
+        assert!(streamed.clone().verify_truncated(&mac[..9], 9).is_err());
+        assert!(streamed.verify_truncated(&mac, 21).is_err());
+        assert!(constant_time_eq(&mac, &mac.clone()));
+        assert!(!constant_time_eq(&mac, &mac[..19]));
+    }
+}
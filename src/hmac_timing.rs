@@ -0,0 +1,465 @@
+//! Timing attack on an HMAC compared with an early exit.
+//!
+//! A server checks the signature of a file, `GET /test?file=..&signature=..`, comparing it
+//! with the HMAC a byte at a time and answering as soon as a byte differs. The more leading
+//! bytes of a signature are right, the slower the answer: the right value of each byte
+//! is the one whose requests take longest. Timings across a network are noisy, so each value
+//! is timed several times, and the slowest few again, until a rank test tells the slowest
+//! apart from the next slowest. The last byte leaks nothing, but the server accepts its
+//! right value.
+
+use crate::encodings;
+use crate::error::{Error, Result};
+use crate::hmac;
+use crate::oracle::{Counting, MacOracle};
+use crate::sha1::Sha1;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long the server waits for a request, before hanging up on the client.
+const SERVER_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long the client waits to connect, and for the answer.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Compares a and b a byte at a time, sleeping for the delay after every equal byte,
+/// and returning at the first byte which differs.
+pub fn insecure_compare(a: &[u8], b: &[u8], delay: Duration) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    for (x, y) in a.iter().zip(b) {
+        if x != y {
+            return false;
+        }
+        thread::sleep(delay);
+    }
+    true
+}
+
+/// An HTTP server on localhost, answering `GET /test?file=..&signature=..` with 200
+/// if the signature, in hex, is the HMAC-SHA1 of the file, and 500 otherwise.
+/// It serves one request at a time, until dropped, and hangs up on clients which
+/// do not send their request in time.
+pub struct LeakyHmacServer {
+    address: SocketAddr,
+    service: Arc<Service>,
+    stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+struct Service {
+    key: Vec<u8>,
+    signature_length: usize,
+    delay: Duration,
+}
+
+impl LeakyHmacServer {
+    /// Starts a server on a free port, whose signatures are HMACs under the key
+    /// truncated to signature_length bytes, compared with a delay per byte.
+    pub fn start(key: &[u8], signature_length: usize, delay: Duration) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let service = Arc::new(Service {
+            key: key.to_vec(),
+            signature_length,
+            delay,
+        });
+        let stopped = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let service = Arc::clone(&service);
+            let stopped = Arc::clone(&stopped);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    // a client which hangs up only loses its own answer
+                    if let Ok(stream) = stream {
+                        let _ = service.respond(stream);
+                    }
+                }
+            })
+        };
+        Ok(LeakyHmacServer {
+            address,
+            service,
+            stopped,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The valid signature of the file.
+    pub fn signature(&self, file: &[u8]) -> Vec<u8> {
+        self.service.signature(file)
+    }
+}
+
+impl Drop for LeakyHmacServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // wakes the server up from waiting for a connection
+        let _ = TcpStream::connect(self.address);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Service {
+    fn signature(&self, file: &[u8]) -> Vec<u8> {
+        let mut signature = hmac::hmac::<Sha1>(&self.key, file);
+        signature.truncate(self.signature_length);
+        signature
+    }
+
+    fn respond(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(SERVER_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            self.status(&request_line)
+        );
+        stream.write_all(response.as_bytes())
+    }
+
+    fn status(&self, request_line: &str) -> &'static str {
+        let target = match request_line.split_whitespace().collect::<Vec<&str>>()[..] {
+            ["GET", target, _] => target,
+            _ => return "400 Bad Request",
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        if path != "/test" {
+            return "404 Not Found";
+        }
+        let (mut file, mut signature) = (None, None);
+        for parameter in query.split('&') {
+            match parameter.split_once('=') {
+                Some(("file", value)) => file = percent_decode(value),
+                Some(("signature", value)) => signature = encodings::hex_as_bytes(value).ok(),
+                _ => {}
+            }
+        }
+        match (file, signature) {
+            (Some(file), Some(signature)) => {
+                if insecure_compare(&self.signature(&file), &signature, self.delay) {
+                    "200 OK"
+                } else {
+                    "500 Internal Server Error"
+                }
+            }
+            _ => "400 Bad Request",
+        }
+    }
+}
+
+/// Asks the server whether a signature of a file is valid, a request per query.
+pub struct HttpMacOracle {
+    address: SocketAddr,
+}
+
+impl HttpMacOracle {
+    pub fn new(address: SocketAddr) -> Self {
+        HttpMacOracle { address }
+    }
+}
+
+impl MacOracle for HttpMacOracle {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        let mut stream = TcpStream::connect_timeout(&self.address, CLIENT_TIMEOUT)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        let request = format!(
+            "GET /test?file={}&signature={} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            percent_encode(message),
+            encodings::bytes_to_hex(mac),
+            self.address
+        );
+        stream.write_all(request.as_bytes())?;
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line)?;
+        match status_line.split_whitespace().nth(1) {
+            Some("200") => Ok(true),
+            Some("500") => Ok(false),
+            _ => Err(Error::Io(format!(
+                "unexpected response `{}`",
+                status_line.trim_end()
+            ))),
+        }
+    }
+}
+
+/// Escapes the bytes for a query string, keeping only the unreserved characters.
+fn percent_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = text.bytes();
+    let mut decoded = vec![];
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let digits = [bytes.next()?, bytes.next()?];
+            let digits = std::str::from_utf8(&digits).ok()?;
+            decoded.push(u8::from_str_radix(digits, 16).ok()?);
+        } else {
+            decoded.push(byte);
+        }
+    }
+    Some(decoded)
+}
+
+/// Parameters of the attack.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of timings of every value of a byte, and of the finalists in every round.
+    pub samples: usize,
+    /// Number of values, the slowest so far, timed again in every round.
+    pub finalists: usize,
+    /// Maximum number of rounds of timing the finalists, before giving up on the byte.
+    pub rounds: usize,
+    /// Score of the Mann–Whitney test which the timings of the slowest value must reach
+    /// against those of the next slowest, to stand out.
+    pub confidence: f64,
+    /// Number of times to go back a byte, when no value of a byte stands out,
+    /// which suggests that the byte before was wrong.
+    pub retries: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            samples: 3,
+            finalists: 8,
+            rounds: 16,
+            confidence: 3.0,
+            retries: 4,
+        }
+    }
+}
+
+/// A MAC recovered by timing the oracle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recovery {
+    pub mac: Vec<u8>,
+    /// Number of queries made to the oracle.
+    pub queries: u64,
+}
+
+/// Finds the MAC of length bytes of the message, a byte at a time, from how long
+/// the oracle takes to reject wrong MACs.
+pub fn recover_mac<O: MacOracle>(
+    oracle: &O,
+    message: &[u8],
+    length: usize,
+    config: &Config,
+) -> Result<Recovery> {
+    if config.samples == 0 || config.rounds == 0 {
+        return Err(Error::Cryptanalysis(
+            "at least one sample and one round are needed to time a byte".to_owned(),
+        ));
+    }
+    let oracle = Counting::new(oracle);
+    let mut mac = vec![];
+    let mut retries = config.retries;
+    while mac.len() < length {
+        let found = if mac.len() + 1 == length {
+            accepted_byte(&oracle, message, &mac)?
+        } else {
+            slowest_byte(&oracle, message, &mac, length, config)?
+        };
+        match found {
+            Some(byte) => mac.push(byte),
+            None if retries > 0 => {
+                retries -= 1;
+                mac.pop();
+            }
+            None => {
+                return Err(Error::Cryptanalysis(format!(
+                    "no value of byte {} stands out",
+                    mac.len()
+                )))
+            }
+        }
+    }
+    Ok(Recovery {
+        mac,
+        queries: oracle.queries(),
+    })
+}
+
+/// The value of the last byte of the MAC which the oracle accepts.
+fn accepted_byte<O: MacOracle>(oracle: &O, message: &[u8], prefix: &[u8]) -> Result<Option<u8>> {
+    for byte in 0..=255 {
+        if oracle.verify(message, &[prefix, &[byte]].concat())? {
+            return Ok(Some(byte));
+        }
+    }
+    Ok(None)
+}
+
+/// The value of the byte after the prefix whose MACs the oracle takes longest to reject,
+/// if it stands out.
+fn slowest_byte<O: MacOracle>(
+    oracle: &O,
+    message: &[u8],
+    prefix: &[u8],
+    length: usize,
+    config: &Config,
+) -> Result<Option<u8>> {
+    let time = |byte: u8, timings: &mut Vec<Duration>| -> Result<()> {
+        let mut mac = [prefix, &[byte]].concat();
+        mac.resize(length, 0);
+        for _ in 0..config.samples {
+            let start = Instant::now();
+            oracle.verify(message, &mac)?;
+            timings.push(start.elapsed());
+        }
+        Ok(())
+    };
+
+    let mut candidates: Vec<(u8, Vec<Duration>)> = (0..=255).map(|byte| (byte, vec![])).collect();
+    for (byte, timings) in candidates.iter_mut() {
+        time(*byte, timings)?;
+    }
+    for _ in 0..config.rounds {
+        candidates.sort_by_key(|(_, timings)| std::cmp::Reverse(median(timings)));
+        candidates.truncate(config.finalists.max(2));
+        for (byte, timings) in candidates.iter_mut() {
+            time(*byte, timings)?;
+        }
+        candidates.sort_by_key(|(_, timings)| std::cmp::Reverse(median(timings)));
+        if mann_whitney(&candidates[0].1, &candidates[1].1) >= config.confidence {
+            return Ok(Some(candidates[0].0));
+        }
+    }
+    Ok(None)
+}
+
+/// The lower median of the timings: of two, the faster, since delays only ever add up.
+fn median(timings: &[Duration]) -> Duration {
+    let mut sorted = timings.to_vec();
+    sorted.sort();
+    sorted[(sorted.len() - 1) / 2]
+}
+
+/// The score of the Mann–Whitney test that the first timings are slower: how many standard
+/// deviations above chance the number of pairs of timings in which the first is slower is.
+/// It only depends on the order of the timings, so outliers do not sway it.
+fn mann_whitney(slower: &[Duration], faster: &[Duration]) -> f64 {
+    let wins: f64 = slower
+        .iter()
+        .flat_map(|a| {
+            faster.iter().map(move |b| match a.cmp(b) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            })
+        })
+        .sum();
+    let (m, n) = (slower.len() as f64, faster.len() as f64);
+    (wins - m * n / 2.0) / (m * n * (m + n + 1.0) / 12.0).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_should_accept_only_the_signature_of_the_file() {
+        let server = LeakyHmacServer::start(b"key", 20, Duration::from_millis(1)).unwrap();
+        let oracle = HttpMacOracle::new(server.address());
+        let file = b"some file/with spaces & symbols";
+        let signature = server.signature(file);
+        assert_eq!(signature, hmac::hmac::<Sha1>(b"key", file));
+        assert_eq!(oracle.verify(file, &signature), Ok(true));
+        assert_eq!(oracle.verify(b"another file", &signature), Ok(false));
+        assert_eq!(oracle.verify(file, &signature[..19]), Ok(false));
+
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        stream.write_all(b"GET /other HTTP/1.1\r\n\r\n").unwrap();
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line).unwrap();
+        assert_eq!(status_line, "HTTP/1.1 404 Not Found\r\n");
+    }
+
+    #[test]
+    fn server_should_hang_up_on_a_silent_client() {
+        let server = LeakyHmacServer::start(b"key", 20, Duration::ZERO).unwrap();
+        let _silent = TcpStream::connect(server.address()).unwrap();
+        let oracle = HttpMacOracle::new(server.address());
+        assert_eq!(oracle.verify(b"file", &server.signature(b"file")), Ok(true));
+
+        let _silent = TcpStream::connect(server.address()).unwrap();
+        let start = Instant::now();
+        drop(server);
+        assert!(start.elapsed() < 2 * SERVER_TIMEOUT);
+    }
+
+    #[test]
+    fn insecure_compare_should_take_longer_the_more_bytes_match() {
+        let delay = Duration::from_millis(5);
+        let start = Instant::now();
+        assert!(!insecure_compare(b"abcd", b"abxx", delay));
+        assert!(start.elapsed() >= 2 * delay);
+        assert!(insecure_compare(b"abcd", b"abcd", Duration::ZERO));
+        assert!(!insecure_compare(b"abcd", b"abc", Duration::ZERO));
+    }
+
+    #[test]
+    fn recover_mac_should_find_the_signature_over_http() {
+        let server =
+            LeakyHmacServer::start(b"YELLOW SUBMARINE", 3, Duration::from_millis(3)).unwrap();
+        let oracle = HttpMacOracle::new(server.address());
+        let config = Config {
+            samples: 2,
+            finalists: 6,
+            ..Config::default()
+        };
+        let recovery = recover_mac(&oracle, b"foo", 3, &config).unwrap();
+        assert_eq!(recovery.mac, server.signature(b"foo"));
+        assert!(recovery.queries > 256);
+    }
+
+    #[test]
+    fn recover_mac_should_reject_zero_samples_or_rounds() {
+        let oracle = crate::oracle::from_fn(|_: &[u8], _: &[u8]| Ok(false));
+        for config in [
+            Config {
+                samples: 0,
+                ..Config::default()
+            },
+            Config {
+                rounds: 0,
+                ..Config::default()
+            },
+        ] {
+            assert!(matches!(
+                recover_mac(&oracle, b"foo", 3, &config),
+                Err(Error::Cryptanalysis(_))
+            ));
+        }
+    }
+}
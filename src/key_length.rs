@@ -0,0 +1,346 @@
+//! Statistical detection of the key length of repeating-key XOR ciphertexts.
+//!
+//! Four independent measures are offered:
+//! - normalized hamming distance, averaged over all pairs of key-sized blocks,
+//! - index of coincidence of the columns of the transposed ciphertext,
+//! - Kasiski examination of the distances between repeated sequences,
+//! - the Friedman test, estimating the key length from the index of coincidence.
+//!
+//! `analyze` combines the first three into a ranked list of candidate key lengths.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use crate::encodings;
+
+/// Parameters for the key length analysis.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Key lengths to consider.
+    pub key_sizes: RangeInclusive<usize>,
+    /// Maximum number of blocks compared pairwise for the hamming distance. All if None.
+    pub max_hamming_blocks: Option<usize>,
+    /// Length of the repeated sequences looked for in the Kasiski examination.
+    pub kasiski_sequence_length: usize,
+    /// Index of coincidence expected of the plaintext (bytes, not letters).
+    pub plaintext_ic: f32,
+    /// A key length is reported as a multiple of a smaller one if the smaller key length
+    /// reaches at least this fraction of its index of coincidence.
+    pub multiple_tolerance: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            key_sizes: 2..=40,
+            max_hamming_blocks: Some(64),
+            kasiski_sequence_length: 3,
+            plaintext_ic: 0.065,
+            multiple_tolerance: 0.9,
+        }
+    }
+}
+
+/// Statistics gathered for a single key length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub key_size: usize,
+    /// Average normalized hamming distance between blocks, lower is better.
+    pub hamming: f32,
+    /// Average index of coincidence of the columns, higher is better.
+    pub ic: f32,
+    /// Number of Kasiski distances the key size divides.
+    pub kasiski_votes: usize,
+    /// Combined score, higher is better.
+    pub score: f32,
+    /// Smallest key size of which this one appears to be a multiple.
+    pub multiple_of: Option<usize>,
+}
+
+/// The outcome of the key length analysis.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    /// Candidates ranked best first. Multiples of better candidates are ranked last.
+    pub candidates: Vec<Candidate>,
+    /// Key length estimated by the Friedman test.
+    pub friedman_estimate: f32,
+}
+
+impl Analysis {
+    /// The best candidate key length, if there was any key size to consider.
+    pub fn best(&self) -> Option<usize> {
+        self.candidates.first().map(|c| c.key_size)
+    }
+}
+
+/// Runs all the tests over the ciphertext for the key sizes in config,
+/// and ranks the candidate key sizes.
+/// Key sizes that leave less than two complete blocks are skipped.
+pub fn analyze(bytes: &[u8], config: &Config) -> Analysis {
+    let distances = kasiski_distances(bytes, config.kasiski_sequence_length);
+
+    let mut candidates: Vec<Candidate> = kasiski_votes(&distances, config.key_sizes.clone())
+        .into_iter()
+        .filter(|(k, _)| bytes.len() >= 2 * k)
+        .map(|(k, votes)| Candidate {
+            key_size: k,
+            hamming: hamming_score(bytes, k, config.max_hamming_blocks),
+            ic: average_column_ic(bytes, k),
+            kasiski_votes: votes,
+            score: 0.,
+            multiple_of: None,
+        })
+        .collect();
+
+    let best_hamming = fold_f32(candidates.iter().map(|c| c.hamming), f32::min);
+    let best_ic = fold_f32(candidates.iter().map(|c| c.ic), f32::max);
+    let best_kasiski = candidates
+        .iter()
+        .map(|c| kasiski_ratio(c, distances.len()))
+        .fold(0., f32::max);
+
+    let ics: HashMap<usize, f32> = candidates.iter().map(|c| (c.key_size, c.ic)).collect();
+    candidates.iter_mut().for_each(|c| {
+        let hamming = if c.hamming > 0. {
+            best_hamming / c.hamming
+        } else {
+            1.
+        };
+        let ic = if best_ic > 0. { c.ic / best_ic } else { 0. };
+        let kasiski = if best_kasiski > 0. {
+            kasiski_ratio(c, distances.len()) / best_kasiski
+        } else {
+            0.
+        };
+        c.score = hamming + ic + kasiski / 2.;
+        c.multiple_of = (1..c.key_size).filter(|d| c.key_size % d == 0).find(|d| {
+            ics.get(d)
+                .is_some_and(|ic| *ic >= c.ic * config.multiple_tolerance)
+        });
+    });
+
+    candidates.sort_by(|a, b| {
+        a.multiple_of
+            .is_some()
+            .cmp(&b.multiple_of.is_some())
+            .then(b.score.total_cmp(&a.score))
+    });
+
+    Analysis {
+        candidates,
+        friedman_estimate: friedman(bytes, config.plaintext_ic),
+    }
+}
+
+/// Average normalized hamming distance between every pair of complete key_size blocks,
+/// using at most max_blocks blocks.
+/// Infinite if there are less than two blocks, e.g. for a key_size of zero.
+/// ```
+/// // "abab" blocks are identical for a key size of 2
+/// assert_eq!(cryptopals::key_length::hamming_score(b"ababab", 2, None), 0.);
+/// ```
+pub fn hamming_score(bytes: &[u8], key_size: usize, max_blocks: Option<usize>) -> f32 {
+    if key_size == 0 {
+        return f32::INFINITY;
+    }
+    let blocks: Vec<&[u8]> = bytes
+        .chunks_exact(key_size)
+        .take(max_blocks.unwrap_or(usize::MAX))
+        .collect();
+
+    let (total, pairs) = blocks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, b1)| blocks[i + 1..].iter().map(move |b2| (*b1, *b2)))
+        .fold((0., 0), |(total, pairs), (b1, b2)| {
+            (
+                total
+                    + encodings::bytes_hamming_distance(b1, b2)
+                        .expect("exact chunks have equal lengths") as f32
+                        / key_size as f32,
+                pairs + 1,
+            )
+        });
+
+    if pairs == 0 {
+        f32::INFINITY
+    } else {
+        total / pairs as f32
+    }
+}
+
+/// Index of coincidence of the bytes: the probability that two bytes
+/// drawn at random (without replacement) are equal.
+/// ```
+/// assert_eq!(cryptopals::key_length::index_of_coincidence(b"aaaa"), 1.);
+/// assert_eq!(cryptopals::key_length::index_of_coincidence(b"abcd"), 0.);
+/// ```
+pub fn index_of_coincidence(bytes: &[u8]) -> f32 {
+    let n = bytes.len();
+    if n < 2 {
+        return 0.;
+    }
+    let mut counts = [0usize; 256];
+    bytes.iter().for_each(|b| counts[*b as usize] += 1);
+    let coincidences: usize = counts.iter().map(|c| c * c.saturating_sub(1)).sum();
+    coincidences as f32 / (n * (n - 1)) as f32
+}
+
+/// Index of coincidence of every column of the ciphertext transposed over key_size,
+/// i.e. of the bytes encrypted with the same key byte.
+pub fn column_ics(bytes: &[u8], key_size: usize) -> Vec<f32> {
+    (0..key_size)
+        .map(|offset| {
+            let column: Vec<u8> = bytes
+                .iter()
+                .skip(offset)
+                .step_by(key_size)
+                .copied()
+                .collect();
+            index_of_coincidence(&column)
+        })
+        .collect()
+}
+
+/// Average of the column indices of coincidence for key_size, zero for a key_size of zero.
+pub fn average_column_ic(bytes: &[u8], key_size: usize) -> f32 {
+    if key_size == 0 {
+        return 0.;
+    }
+    column_ics(bytes, key_size).iter().sum::<f32>() / key_size as f32
+}
+
+/// Kasiski examination: finds every sequence of sequence_length bytes
+/// which repeats in the ciphertext, and returns the distances between
+/// consecutive occurrences.
+/// The key length is likely to divide most of these distances.
+/// ```
+/// let distances = cryptopals::key_length::kasiski_distances(b"xyzabcxyzdefxyz", 3);
+/// assert_eq!(distances, vec![6, 6]);
+/// ```
+pub fn kasiski_distances(bytes: &[u8], sequence_length: usize) -> Vec<usize> {
+    if sequence_length == 0 {
+        return vec![];
+    }
+    let mut last_seen: HashMap<&[u8], usize> = HashMap::new();
+    let mut distances = vec![];
+    bytes
+        .windows(sequence_length)
+        .enumerate()
+        .for_each(|(position, sequence)| {
+            if let Some(previous) = last_seen.insert(sequence, position) {
+                distances.push(position - previous);
+            }
+        });
+    distances
+}
+
+/// Counts how often each key size in the range divides a Kasiski distance.
+pub fn kasiski_votes(distances: &[usize], key_sizes: RangeInclusive<usize>) -> Vec<(usize, usize)> {
+    key_sizes
+        .filter(|k| *k > 0)
+        .map(|k| (k, distances.iter().filter(|d| *d % k == 0).count()))
+        .collect()
+}
+
+/// Friedman test: estimates the key length from the index of coincidence of
+/// the whole ciphertext, the index of coincidence expected of the plaintext,
+/// and that of uniformly random bytes.
+pub fn friedman(bytes: &[u8], plaintext_ic: f32) -> f32 {
+    let random_ic = 1. / 256.;
+    let n = bytes.len() as f32;
+    let ic = index_of_coincidence(bytes);
+    let denominator = (n - 1.) * ic - n * random_ic + plaintext_ic;
+    if denominator <= 0. {
+        f32::INFINITY
+    } else {
+        (plaintext_ic - random_ic) * n / denominator
+    }
+}
+
+/// Kasiski votes relative to what a random key size would get (1 in key_size distances).
+fn kasiski_ratio(candidate: &Candidate, total: usize) -> f32 {
+    if total == 0 {
+        0.
+    } else {
+        (candidate.kasiski_votes * candidate.key_size) as f32 / total as f32
+    }
+}
+
+fn fold_f32<I: Iterator<Item = f32>>(values: I, f: fn(f32, f32) -> f32) -> f32 {
+    values.filter(|v| v.is_finite()).fold(f32::NAN, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encrypt;
+
+    const TEXT: &str = "It was the best of times, it was the worst of times, it was the age of \
+        wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of \
+        incredulity, it was the season of Light, it was the season of Darkness, it was the \
+        spring of hope, it was the winter of despair, we had everything before us, we had \
+        nothing before us, we were all going direct to Heaven, we were all going direct the \
+        other way - in short, the period was so far like the present period, that some of its \
+        noisiest authorities insisted on its being received, for good or for evil, in the \
+        superlative degree of comparison only.";
+
+    fn encrypted(key: &str) -> Vec<u8> {
+        encrypt::repeated_key_xor(TEXT.as_bytes(), key.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn analyze_should_rank_the_key_length_first() {
+        let analysis = analyze(&encrypted("DICKENS"), &Config::default());
+        assert_eq!(analysis.best(), Some(7));
+    }
+
+    #[test]
+    fn analyze_should_flag_multiples_of_the_key_length() {
+        let analysis = analyze(&encrypted("KEY"), &Config::default());
+        assert_eq!(analysis.best(), Some(3));
+        let six = analysis
+            .candidates
+            .iter()
+            .find(|c| c.key_size == 6)
+            .unwrap();
+        assert_eq!(six.multiple_of, Some(3));
+    }
+
+    #[test]
+    fn analyze_should_respect_the_configured_range() {
+        let config = Config {
+            key_sizes: 5..=9,
+            ..Config::default()
+        };
+        let analysis = analyze(&encrypted("DICKENS"), &config);
+        let sizes: Vec<usize> = analysis.candidates.iter().map(|c| c.key_size).collect();
+        assert_eq!(sizes.len(), 5);
+        assert!(sizes.iter().all(|k| (5..=9).contains(k)));
+    }
+
+    #[test]
+    fn friedman_should_estimate_close_to_the_key_length() {
+        let estimate = friedman(&encrypted("KEY"), 0.065);
+        assert!(estimate > 1.5 && estimate < 6., "estimate = {}", estimate);
+    }
+
+    #[test]
+    fn hamming_score_should_use_all_block_pairs() {
+        // blocks "aa", "ab", "bb": distances 2, 4, 2 bits over 2 bytes
+        let expected = (1. + 2. + 1.) / 3.;
+        assert_eq!(hamming_score(b"aaabbb", 2, None), expected);
+        assert_eq!(hamming_score(b"aaabbb", 2, Some(2)), 1.);
+    }
+
+    #[test]
+    fn scores_should_handle_a_zero_key_size() {
+        assert_eq!(hamming_score(b"aaabbb", 0, None), f32::INFINITY);
+        assert_eq!(average_column_ic(b"aaabbb", 0), 0.);
+    }
+
+    #[test]
+    fn kasiski_votes_should_count_divisible_distances() {
+        assert_eq!(kasiski_votes(&[6, 12, 9], 2..=3), vec![(2, 2), (3, 3)]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Length extension attacks on secret-prefix MACs, MAC = H(secret || message)
+//! (cryptopals challenge 29).
+//!
+//! The MAC of a message is the state of the hash after secret || message || padding,
+//! so whoever has it can go on hashing an extension without knowing the secret,
+//! and gets the MAC of message || padding || extension. The padding depends on
+//! the length of the secret, which is guessed within a range until the MAC verifies.
+//! This works alike for every Merkle–Damgård hash whose digest is its whole state:
+//! MD4, MD5, SHA-1, SHA-256 and SHA-512.
+
+use crate::error::{Error, Result};
+use crate::merkle_damgard::MerkleDamgard;
+use crate::oracle::{Counting, MacOracle};
+use crate::random::Random;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+
+/// Authenticates messages as H(secret || message), with a secret of random length.
+pub struct SecretPrefixMac<H> {
+    secret: Vec<u8>,
+    hash: PhantomData<fn() -> H>,
+}
+
+impl<H: MerkleDamgard> SecretPrefixMac<H> {
+    /// A service with a random secret of a length in the range.
+    pub fn new(secret_lengths: RangeInclusive<usize>, random: &mut Random) -> Self {
+        let length = random.range(*secret_lengths.start(), *secret_lengths.end() + 1);
+        SecretPrefixMac {
+            secret: random.bytes(length),
+            hash: PhantomData,
+        }
+    }
+
+    pub fn mac(&self, message: &[u8]) -> Vec<u8> {
+        H::digest(&[&self.secret[..], message].concat())
+    }
+}
+
+impl<H: MerkleDamgard> MacOracle for SecretPrefixMac<H> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        Ok(self.mac(message) == mac)
+    }
+}
+
+/// A message extended without the secret, and its MAC.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forgery {
+    /// The original message, its glue padding, and the extension.
+    pub message: Vec<u8>,
+    pub mac: Vec<u8>,
+    /// The length of the secret the forgery assumed.
+    pub secret_length: usize,
+    /// Number of queries made to the oracle to find the length of the secret.
+    pub queries: u64,
+}
+
+/// Extends the message, whose MAC is mac, with extension,
+/// assuming a secret of the given length. The result is only valid if it is right.
+pub fn extend<H: MerkleDamgard>(
+    message: &[u8],
+    mac: &[u8],
+    extension: &[u8],
+    secret_length: usize,
+) -> Result<Forgery> {
+    let length = (secret_length + message.len()) as u64;
+    let glue = H::padding(length);
+    let mut hash = H::from_digest(mac, length + glue.len() as u64)?;
+    hash.update(extension);
+    Ok(Forgery {
+        message: [message, &glue, extension].concat(),
+        mac: hash.finalize(),
+        secret_length,
+        queries: 0,
+    })
+}
+
+/// Extends the message, whose MAC is mac, with extension, trying every length of the secret
+/// in the range until the oracle accepts the forgery.
+pub fn forge<H: MerkleDamgard, O: MacOracle>(
+    oracle: &O,
+    message: &[u8],
+    mac: &[u8],
+    extension: &[u8],
+    secret_lengths: RangeInclusive<usize>,
+) -> Result<Forgery> {
+    let oracle = Counting::new(oracle);
+    for secret_length in secret_lengths {
+        let forgery = extend::<H>(message, mac, extension, secret_length)?;
+        if oracle.verify(&forgery.message, &forgery.mac)? {
+            return Ok(Forgery {
+                queries: oracle.queries(),
+                ..forgery
+            });
+        }
+    }
+    Err(Error::Cryptanalysis(
+        "no secret length in the range gives a valid forgery".to_owned(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::md4::Md4;
+    use crate::md5::Md5;
+    use crate::sha1::Sha1;
+
+    const MESSAGE: &[u8] =
+        b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+
+    #[test]
+    fn forge_should_find_the_secret_length_and_a_valid_mac() {
+        let mut random = Random::with_seed(29);
+        for _ in 0..4 {
+            let service = SecretPrefixMac::<Sha1>::new(0..=64, &mut random);
+            let forgery = forge::<Sha1, _>(
+                &service,
+                MESSAGE,
+                &service.mac(MESSAGE),
+                b";admin=true",
+                0..=64,
+            )
+            .unwrap();
+            assert_eq!(forgery.secret_length, service.secret.len());
+            assert_eq!(forgery.queries, forgery.secret_length as u64 + 1);
+            assert!(forgery.message.ends_with(b";admin=true"));
+            assert!(service.verify(&forgery.message, &forgery.mac).unwrap());
+        }
+    }
+
+    #[test]
+    fn forge_should_fail_outside_the_range() {
+        let service = SecretPrefixMac::<Sha1>::new(20..=20, &mut Random::with_seed(1));
+        let mac = service.mac(MESSAGE);
+        assert!(forge::<Sha1, _>(&service, MESSAGE, &mac, b"x", 0..=19).is_err());
+        assert!(extend::<Sha1>(MESSAGE, &mac[..19], b"x", 20).is_err());
+    }
+
+    #[test]
+    fn forge_should_work_for_md4_and_md5() {
+        let mut random = Random::with_seed(30);
+        let service = SecretPrefixMac::<Md4>::new(1..=32, &mut random);
+        let mac = service.mac(MESSAGE);
+        let forgery = forge::<Md4, _>(&service, MESSAGE, &mac, b";admin=true", 1..=32).unwrap();
+        assert!(service.verify(&forgery.message, &forgery.mac).unwrap());
+
+        let service = SecretPrefixMac::<Md5>::new(1..=32, &mut random);
+        let mac = service.mac(MESSAGE);
+        let forgery = forge::<Md5, _>(&service, MESSAGE, &mac, b";admin=true", 1..=32).unwrap();
+        assert!(service.verify(&forgery.message, &forgery.mac).unwrap());
+        // the glue padding ends with the length in bits, little-endian
+        let bits = 8 * (forgery.secret_length + MESSAGE.len()) as u64;
+        let end = forgery.message.len() - b";admin=true".len();
+        assert_eq!(forgery.message[end - 8..end], bits.to_le_bytes());
+    }
+}
@@ -1,5 +1,35 @@
 pub mod aes128;
+pub mod bit_flipping;
+pub mod block_analysis;
+pub mod cbc_key_as_iv;
+pub mod challenges;
+pub mod cookie;
+pub mod crib;
 pub mod decrypt;
+pub mod ecb_byte_at_a_time;
+pub mod ecb_cut_and_paste;
 pub mod encodings;
 pub mod encrypt;
+pub mod error;
+pub mod fixed_nonce_ctr;
+pub mod hash;
 pub mod hexaa;
+pub mod hmac;
+pub mod hmac_timing;
+pub mod key_length;
+pub mod length_extension;
+pub mod many_time_pad;
+pub mod md4;
+pub mod md5;
+pub mod merkle_damgard;
+pub mod mt19937;
+pub mod oracle;
+pub mod padding;
+pub mod padding_oracle;
+pub mod prng;
+pub mod random;
+pub mod random_access_ctr;
+pub mod scoring;
+pub mod sha1;
+pub mod sha2;
+pub mod sha3;
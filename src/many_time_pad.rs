@@ -0,0 +1,245 @@
+//! Breaking messages encrypted with the same XOR keystream (a reused one-time pad,
+//! or a stream cipher with a fixed nonce).
+//!
+//! Byte i of every ciphertext is encrypted with the same keystream byte i,
+//! so the column of i-th bytes of all the ciphertexts is a single-byte XOR ciphertext.
+//! And any plaintext guessed for one message reveals the keystream,
+//! and hence the plaintext of every other message, at the same positions.
+
+use crate::decrypt;
+use crate::scoring::Scorer;
+
+/// The state of an attack on a set of ciphertexts sharing a keystream.
+/// Keystream bytes are None until they are solved or guessed.
+pub struct ManyTimePad {
+    ciphertexts: Vec<Vec<u8>>,
+    keystream: Vec<Option<u8>>,
+}
+
+/// The outcome of placing a crib in one of the ciphertexts at an offset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CribDrag {
+    /// Index of the ciphertext the crib was placed in.
+    pub ciphertext: usize,
+    pub offset: usize,
+    /// Keystream bytes implied by the crib.
+    pub keystream: Vec<u8>,
+    /// Plaintext fragment of every ciphertext at the offset, as implied by the crib.
+    /// Shorter ciphertexts give shorter, possibly empty, fragments.
+    pub fragments: Vec<Vec<u8>>,
+    /// Score of all the fragments of the other ciphertexts, higher is better.
+    pub score: f32,
+}
+
+impl ManyTimePad {
+    pub fn new(ciphertexts: Vec<Vec<u8>>) -> Self {
+        let length = ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0);
+        ManyTimePad {
+            ciphertexts,
+            keystream: vec![None; length],
+        }
+    }
+
+    pub fn ciphertexts(&self) -> &[Vec<u8>] {
+        &self.ciphertexts
+    }
+
+    pub fn keystream(&self) -> &[Option<u8>] {
+        &self.keystream
+    }
+
+    /// The i-th bytes of all ciphertexts long enough to have one.
+    pub fn column(&self, i: usize) -> Vec<u8> {
+        self.ciphertexts
+            .iter()
+            .filter_map(|c| c.get(i))
+            .copied()
+            .collect()
+    }
+
+    /// Candidate keystream bytes for column i, with their scores, best first.
+    pub fn column_candidates<S: Scorer>(&self, i: usize, scorer: &S) -> Vec<(u8, f32)> {
+        decrypt::single_byte_xor_candidates(&self.column(i), scorer)
+    }
+
+    /// Solves every keystream byte not yet known as a single-byte XOR of its column.
+    pub fn solve<S: Scorer>(&mut self, scorer: &S) {
+        (0..self.keystream.len()).for_each(|i| {
+            if self.keystream[i].is_none() {
+                self.keystream[i] = Some(decrypt::single_byte_xor(&self.column(i), scorer).0);
+            }
+        });
+    }
+
+    /// Sets a keystream byte, e.g. to correct a statistical guess.
+    pub fn set_keystream_byte(&mut self, i: usize, byte: u8) {
+        self.keystream[i] = Some(byte);
+    }
+
+    /// Accepts a guess that ciphertext number `ciphertext` decrypts to plaintext at offset,
+    /// fixing the keystream bytes it implies.
+    /// Bytes of the guess past the end of that ciphertext are ignored.
+    pub fn guess(&mut self, ciphertext: usize, offset: usize, plaintext: &[u8]) {
+        let encrypted = &self.ciphertexts[ciphertext];
+        let keystream = &mut self.keystream;
+        plaintext
+            .iter()
+            .zip(encrypted.iter().skip(offset))
+            .enumerate()
+            .for_each(|(i, (p, c))| keystream[offset + i] = Some(p ^ c));
+    }
+
+    /// Places the crib at every offset of every ciphertext, and reports
+    /// the plaintext fragments it implies for all the ciphertexts,
+    /// ranked by how plausible the fragments of the other ciphertexts are.
+    pub fn crib_drag<S: Scorer>(&self, crib: &[u8], scorer: &S) -> Vec<CribDrag> {
+        let mut drags: Vec<CribDrag> = self
+            .ciphertexts
+            .iter()
+            .enumerate()
+            .flat_map(|(index, encrypted)| {
+                (0..(encrypted.len() + 1).saturating_sub(crib.len()))
+                    .map(move |offset| self.drag(index, offset, crib, scorer))
+            })
+            .collect();
+        drags.sort_by(|a, b| b.score.total_cmp(&a.score));
+        drags
+    }
+
+    fn drag<S: Scorer>(&self, index: usize, offset: usize, crib: &[u8], scorer: &S) -> CribDrag {
+        let keystream: Vec<u8> = crib
+            .iter()
+            .zip(&self.ciphertexts[index][offset..])
+            .map(|(p, c)| p ^ c)
+            .collect();
+        let fragments: Vec<Vec<u8>> = self
+            .ciphertexts
+            .iter()
+            .map(|encrypted| {
+                encrypted
+                    .iter()
+                    .skip(offset)
+                    .zip(keystream.iter())
+                    .map(|(c, k)| c ^ k)
+                    .collect()
+            })
+            .collect();
+        let score = fragments
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, fragment)| scorer.score(fragment))
+            .sum();
+        CribDrag {
+            ciphertext: index,
+            offset,
+            keystream,
+            fragments,
+            score,
+        }
+    }
+
+    /// Decrypts every ciphertext with the known keystream bytes.
+    /// Bytes whose keystream byte is unknown are None.
+    pub fn plaintexts(&self) -> Vec<Vec<Option<u8>>> {
+        self.ciphertexts
+            .iter()
+            .map(|encrypted| {
+                encrypted
+                    .iter()
+                    .zip(self.keystream.iter())
+                    .map(|(c, k)| k.map(|k| c ^ k))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Renders the plaintexts for display, showing unknown bytes as `unknown`
+    /// and bytes which are not printable ASCII as '.'.
+    pub fn render(&self, unknown: char) -> Vec<String> {
+        self.plaintexts()
+            .iter()
+            .map(|plaintext| {
+                plaintext
+                    .iter()
+                    .map(|byte| match byte {
+                        None => unknown,
+                        Some(b) if b.is_ascii_graphic() || *b == b' ' => *b as char,
+                        Some(_) => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::LetterFrequency;
+
+    const MESSAGES: [&str; 10] = [
+        "we attack at dawn from the east side",
+        "the eagle has landed on the west bank",
+        "send more troops to the northern ridge",
+        "retreat is not an option for us now",
+        "meet me at the old mill after sunset",
+        "the password is written in the ledger",
+        "do not trust the courier with the map",
+        "our position is known to the enemy",
+        "the bridge will be destroyed at noon",
+        "bring the supplies to the river camp",
+    ];
+
+    fn encrypted() -> Vec<Vec<u8>> {
+        let keystream: Vec<u8> = (0..64u32).map(|i| (i * 167 + 13) as u8).collect();
+        MESSAGES
+            .iter()
+            .map(|m| {
+                m.bytes()
+                    .zip(keystream.iter())
+                    .map(|(p, k)| p ^ k)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solve_should_recover_most_of_the_plaintexts() {
+        let mut pad = ManyTimePad::new(encrypted());
+        pad.solve(&LetterFrequency::new());
+        let recovered = pad.render('?');
+        let correct = recovered[0]
+            .bytes()
+            .zip(MESSAGES[0].bytes())
+            .take(30)
+            .filter(|(a, b)| a == b)
+            .count();
+        assert!(correct >= 25, "recovered {:?}", recovered[0]);
+    }
+
+    #[test]
+    fn guess_should_reveal_the_other_plaintexts() {
+        let mut pad = ManyTimePad::new(encrypted());
+        pad.guess(0, 0, b"we attack");
+        let plaintexts = pad.render('?');
+        assert!(plaintexts[1].starts_with("the eagle?"));
+        assert!(plaintexts[9].starts_with("bring the?"));
+    }
+
+    #[test]
+    fn crib_drag_should_rank_the_right_placement_high() {
+        let pad = ManyTimePad::new(encrypted());
+        let drags = pad.crib_drag(b"the password", &LetterFrequency::new());
+        let best = &drags[0];
+        assert_eq!((best.ciphertext, best.offset), (5, 0));
+        assert_eq!(best.fragments[4], b"meet me at t".to_vec());
+    }
+
+    #[test]
+    fn crib_drag_should_truncate_fragments_of_short_ciphertexts() {
+        let pad = ManyTimePad::new(vec![vec![1, 2, 3, 4], vec![5, 6]]);
+        let drag = pad.drag(0, 1, b"ab", &LetterFrequency::new());
+        assert_eq!(drag.fragments[1], vec![6 ^ 2 ^ b'a']);
+    }
+}
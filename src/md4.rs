@@ -0,0 +1,206 @@
+//! MD4 (RFC 1320), with its chaining state exposed.
+//!
+//! The little-endian Merkle–Damgård hash which MD5 was derived from,
+//! with three rounds of simpler operations.
+
+use crate::error::Result;
+use crate::hash::Hash;
+use crate::merkle_damgard::{self, Blocks, Endianness, MerkleDamgard};
+use std::convert::TryInto;
+
+pub const BLOCK_SIZE: usize = 64;
+pub const DIGEST_SIZE: usize = 16;
+
+const INITIAL_STATE: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+/// Order in which the operations of the second and third rounds take the words of the block.
+const ORDER: [[usize; 16]; 2] = [
+    [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+    [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+];
+
+/// Rotations of the four operations of each round.
+const SHIFTS: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+
+/// An MD4 computation in progress, fed with update.
+/// ```
+/// use cryptopals::{encodings, md4::Md4};
+/// let mut md4 = Md4::new();
+/// md4.update(b"message ");
+/// md4.update(b"digest");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&md4.finalize()),
+///     "d9130a8164549fe818874806e1c7014b");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Md4 {
+    state: [u32; 4],
+    blocks: Blocks,
+}
+
+impl Default for Md4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md4 {
+    pub fn new() -> Self {
+        Md4 {
+            state: INITIAL_STATE,
+            blocks: Blocks::new(BLOCK_SIZE, 0),
+        }
+    }
+
+    /// Continues a computation from its state after length bytes,
+    /// checked with `merkle_damgard::check_whole_blocks`.
+    pub fn from_state(state: [u32; 4], length: u64) -> Result<Self> {
+        merkle_damgard::check_whole_blocks(length, BLOCK_SIZE)?;
+        Ok(Md4 {
+            state,
+            blocks: Blocks::new(BLOCK_SIZE, length),
+        })
+    }
+
+    /// Continues a computation from a digest, the state after hashing
+    /// length bytes, message and padding included.
+    pub fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Self::from_state(state_from_digest(digest)?, length)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress(state, block));
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.blocks.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = padding(self.length());
+        self.update(&padding);
+        self.state
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+}
+
+impl Hash for Md4 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const DIGEST_SIZE: usize = DIGEST_SIZE;
+
+    fn new() -> Self {
+        Md4::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Md4::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Md4::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Md4 {
+    fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Md4::from_digest(digest, length)
+    }
+
+    fn padding(length: u64) -> Vec<u8> {
+        padding(length)
+    }
+}
+
+/// The MD4 digest of the data.
+pub fn md4(data: &[u8]) -> Vec<u8> {
+    let mut md4 = Md4::new();
+    md4.update(data);
+    md4.finalize()
+}
+
+/// The padding which follows a message of length bytes,
+/// ending with the length in bits, little-endian.
+pub fn padding(length: u64) -> Vec<u8> {
+    merkle_damgard::padding(length, BLOCK_SIZE, Endianness::Little)
+}
+
+/// The chaining state a digest was read from.
+pub fn state_from_digest(digest: &[u8]) -> Result<[u32; 4]> {
+    merkle_damgard::check_digest_length(digest, DIGEST_SIZE)?;
+    let mut state = [0; 4];
+    state
+        .iter_mut()
+        .zip(digest.chunks_exact(4))
+        .for_each(|(word, bytes)| *word = u32::from_le_bytes(bytes.try_into().unwrap()));
+    Ok(state)
+}
+
+fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let mut x = [0u32; 16];
+    block
+        .chunks_exact(4)
+        .enumerate()
+        .for_each(|(i, bytes)| x[i] = u32::from_le_bytes(bytes.try_into().unwrap()));
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..48 {
+        let (f, k, constant) = match i / 16 {
+            0 => ((b & c) | (!b & d), i, 0),
+            1 => ((b & c) | (b & d) | (c & d), ORDER[0][i % 16], 0x5a82_7999),
+            _ => (b ^ c ^ d, ORDER[1][i % 16], 0x6ed9_eba1),
+        };
+        let rotated = a
+            .wrapping_add(f)
+            .wrapping_add(x[k])
+            .wrapping_add(constant)
+            .rotate_left(SHIFTS[i / 16][i % 4]);
+        a = d;
+        d = c;
+        c = b;
+        b = rotated;
+    }
+    state
+        .iter_mut()
+        .zip([a, b, c, d])
+        .for_each(|(word, value)| *word = word.wrapping_add(value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+
+    #[test]
+    fn md4_should_match_rfc_1320_vectors() {
+        let vectors: [(&[u8], &str); 6] = [
+            (b"", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            (b"a", "bde52cb31de33e46245e05fbdbd6fb24"),
+            (b"abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            (
+                b"abcdefghijklmnopqrstuvwxyz",
+                "d79e1c308aa5bbcdeea8ed63df412da9",
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "043f8582f241db351ce627e153e7f0e4",
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+        for (message, digest) in vectors {
+            assert_eq!(encodings::bytes_to_hex(&md4(message)), digest);
+        }
+    }
+}
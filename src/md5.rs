@@ -0,0 +1,275 @@
+//! MD5 (RFC 1321), with its chaining state exposed.
+//!
+//! A Merkle–Damgård hash like SHA-1, but little-endian: the state words are read
+//! from and written to the digest little-endian, and the padding ends with the
+//! length in bits little-endian.
+
+use crate::error::Result;
+use crate::hash::Hash;
+use crate::merkle_damgard::{self, Blocks, Endianness, MerkleDamgard};
+use std::convert::TryInto;
+
+pub const BLOCK_SIZE: usize = 64;
+pub const DIGEST_SIZE: usize = 16;
+
+const INITIAL_STATE: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+/// The integer parts of 2^32 times the sines of 1 to 64.
+const SINES: [u32; 64] = [
+    0xd76a_a478,
+    0xe8c7_b756,
+    0x2420_70db,
+    0xc1bd_ceee,
+    0xf57c_0faf,
+    0x4787_c62a,
+    0xa830_4613,
+    0xfd46_9501,
+    0x6980_98d8,
+    0x8b44_f7af,
+    0xffff_5bb1,
+    0x895c_d7be,
+    0x6b90_1122,
+    0xfd98_7193,
+    0xa679_438e,
+    0x49b4_0821,
+    0xf61e_2562,
+    0xc040_b340,
+    0x265e_5a51,
+    0xe9b6_c7aa,
+    0xd62f_105d,
+    0x0244_1453,
+    0xd8a1_e681,
+    0xe7d3_fbc8,
+    0x21e1_cde6,
+    0xc337_07d6,
+    0xf4d5_0d87,
+    0x455a_14ed,
+    0xa9e3_e905,
+    0xfcef_a3f8,
+    0x676f_02d9,
+    0x8d2a_4c8a,
+    0xfffa_3942,
+    0x8771_f681,
+    0x6d9d_6122,
+    0xfde5_380c,
+    0xa4be_ea44,
+    0x4bde_cfa9,
+    0xf6bb_4b60,
+    0xbebf_bc70,
+    0x289b_7ec6,
+    0xeaa1_27fa,
+    0xd4ef_3085,
+    0x0488_1d05,
+    0xd9d4_d039,
+    0xe6db_99e5,
+    0x1fa2_7cf8,
+    0xc4ac_5665,
+    0xf429_2244,
+    0x432a_ff97,
+    0xab94_23a7,
+    0xfc93_a039,
+    0x655b_59c3,
+    0x8f0c_cc92,
+    0xffef_f47d,
+    0x8584_5dd1,
+    0x6fa8_7e4f,
+    0xfe2c_e6e0,
+    0xa301_4314,
+    0x4e08_11a1,
+    0xf753_7e82,
+    0xbd3a_f235,
+    0x2ad7_d2bb,
+    0xeb86_d391,
+];
+
+/// Rotations of the four operations of each round.
+const SHIFTS: [[u32; 4]; 4] = [
+    [7, 12, 17, 22],
+    [5, 9, 14, 20],
+    [4, 11, 16, 23],
+    [6, 10, 15, 21],
+];
+
+/// An MD5 computation in progress, fed with update.
+/// ```
+/// use cryptopals::{encodings, md5::Md5};
+/// let mut md5 = Md5::new();
+/// md5.update(b"message ");
+/// md5.update(b"digest");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&md5.finalize()),
+///     "f96b697d7cb7938d525a2f31aaf161d0");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Md5 {
+    state: [u32; 4],
+    blocks: Blocks,
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Md5 {
+            state: INITIAL_STATE,
+            blocks: Blocks::new(BLOCK_SIZE, 0),
+        }
+    }
+
+    /// Continues a computation from its state after length bytes,
+    /// checked with `merkle_damgard::check_whole_blocks`.
+    pub fn from_state(state: [u32; 4], length: u64) -> Result<Self> {
+        merkle_damgard::check_whole_blocks(length, BLOCK_SIZE)?;
+        Ok(Md5 {
+            state,
+            blocks: Blocks::new(BLOCK_SIZE, length),
+        })
+    }
+
+    /// Continues a computation from a digest, the state after hashing
+    /// length bytes, message and padding included.
+    pub fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Self::from_state(state_from_digest(digest)?, length)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress(state, block));
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.blocks.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = padding(self.length());
+        self.update(&padding);
+        self.state
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+}
+
+impl Hash for Md5 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const DIGEST_SIZE: usize = DIGEST_SIZE;
+
+    fn new() -> Self {
+        Md5::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Md5::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Md5::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Md5 {
+    fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Md5::from_digest(digest, length)
+    }
+
+    fn padding(length: u64) -> Vec<u8> {
+        padding(length)
+    }
+}
+
+/// The MD5 digest of the data.
+pub fn md5(data: &[u8]) -> Vec<u8> {
+    let mut md5 = Md5::new();
+    md5.update(data);
+    md5.finalize()
+}
+
+/// The padding which follows a message of length bytes,
+/// ending with the length in bits, little-endian.
+pub fn padding(length: u64) -> Vec<u8> {
+    merkle_damgard::padding(length, BLOCK_SIZE, Endianness::Little)
+}
+
+/// The chaining state a digest was read from.
+pub fn state_from_digest(digest: &[u8]) -> Result<[u32; 4]> {
+    merkle_damgard::check_digest_length(digest, DIGEST_SIZE)?;
+    let mut state = [0; 4];
+    state
+        .iter_mut()
+        .zip(digest.chunks_exact(4))
+        .for_each(|(word, bytes)| *word = u32::from_le_bytes(bytes.try_into().unwrap()));
+    Ok(state)
+}
+
+fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let mut m = [0u32; 16];
+    block
+        .chunks_exact(4)
+        .enumerate()
+        .for_each(|(i, bytes)| m[i] = u32::from_le_bytes(bytes.try_into().unwrap()));
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let rotated = a
+            .wrapping_add(f)
+            .wrapping_add(SINES[i])
+            .wrapping_add(m[g])
+            .rotate_left(SHIFTS[i / 16][i % 4]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(rotated);
+    }
+    state
+        .iter_mut()
+        .zip([a, b, c, d])
+        .for_each(|(word, value)| *word = word.wrapping_add(value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+
+    #[test]
+    fn md5_should_match_rfc_1321_vectors() {
+        let vectors: [(&[u8], &str); 6] = [
+            (b"", "d41d8cd98f00b204e9800998ecf8427e"),
+            (b"a", "0cc175b9c0f1b6a831c399e269772661"),
+            (b"abc", "900150983cd24fb0d6963f7d28e17f72"),
+            (
+                b"abcdefghijklmnopqrstuvwxyz",
+                "c3fcd3d76192e4007dfb496cca67e13b",
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+        for (message, digest) in vectors {
+            assert_eq!(encodings::bytes_to_hex(&md5(message)), digest);
+        }
+    }
+}
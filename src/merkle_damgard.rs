@@ -0,0 +1,153 @@
+//! The Merkle–Damgård construction, shared by MD4, MD5, SHA-1 and SHA-2.
+//!
+//! The message, padded with 0x80, zeros and its length in bits, is compressed a block
+//! at a time into a chaining state, and the digest is the final state. So a digest,
+//! and the length hashed, are all it takes to go on hashing, which the hashes expose
+//! for length extension.
+
+use crate::error::{Error, Result};
+use crate::hash::Hash;
+
+/// A Merkle–Damgård hash whose computation can be continued from a digest.
+pub trait MerkleDamgard: Hash {
+    /// Continues a computation from a digest, the state after hashing
+    /// length bytes, message and padding included.
+    fn from_digest(digest: &[u8], length: u64) -> Result<Self>;
+
+    /// The padding which follows a message of length bytes.
+    fn padding(length: u64) -> Vec<u8>;
+}
+
+/// Byte order of the words of the state, and of the length in the padding.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+/// The padding which follows a message of length bytes: 0x80, zeros,
+/// and the length in bits, in a field an eighth of a block long at the end of a block.
+/// ```
+/// use cryptopals::merkle_damgard::{padding, Endianness};
+/// let padding = padding(60, 64, Endianness::Little);
+/// assert_eq!(padding.len(), 68);
+/// assert_eq!(padding[padding.len() - 8..padding.len() - 6], [0xe0, 0x01]);
+/// ```
+pub fn padding(length: u64, block_size: usize, endianness: Endianness) -> Vec<u8> {
+    let field = block_size / 8;
+    let zeros = (block_size * 2 - 1 - field - length as usize % block_size) % block_size;
+    let mut padding = vec![0x80];
+    padding.resize(1 + zeros, 0);
+    let bits = (length as u128) * 8;
+    let bits = match endianness {
+        Endianness::Big => bits.to_be_bytes(),
+        Endianness::Little => bits.to_le_bytes(),
+    };
+    padding.extend_from_slice(match endianness {
+        Endianness::Big => &bits[16 - field..],
+        Endianness::Little => &bits[..field],
+    });
+    padding
+}
+
+/// Checks that length bytes hashed are a whole number of blocks, the only lengths
+/// after which a hash has no data left over, and can continue from its chaining state.
+pub fn check_whole_blocks(length: u64, block_size: usize) -> Result<()> {
+    if !length.is_multiple_of(block_size as u64) {
+        return Err(Error::LengthMismatch {
+            expected: (length as usize).next_multiple_of(block_size),
+            actual: length as usize,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a digest, to read a chaining state from, is of the expected size.
+pub fn check_digest_length(digest: &[u8], expected: usize) -> Result<()> {
+    if digest.len() != expected {
+        return Err(Error::LengthMismatch {
+            expected,
+            actual: digest.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Cuts the data hashed into blocks, keeping the last incomplete block until it fills up,
+/// and counts the bytes hashed.
+#[derive(Clone, Debug)]
+pub(crate) struct Blocks {
+    block_size: usize,
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl Blocks {
+    /// Blocks after length bytes, which must be a whole number of blocks, were hashed.
+    pub(crate) fn new(block_size: usize, length: u64) -> Self {
+        Blocks {
+            block_size,
+            buffer: Vec::with_capacity(block_size),
+            length,
+        }
+    }
+
+    pub(crate) fn length(&self) -> u64 {
+        self.length
+    }
+
+    pub(crate) fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Passes every block the data completes to compress.
+    pub(crate) fn update<F: FnMut(&[u8])>(&mut self, data: &[u8], mut compress: F) {
+        self.length += data.len() as u64;
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let taken = data.len().min(self.block_size - self.buffer.len());
+            self.buffer.extend_from_slice(&data[..taken]);
+            data = &data[taken..];
+            if self.buffer.len() < self.block_size {
+                return;
+            }
+            compress(&self.buffer);
+            self.buffer.clear();
+        }
+        let mut blocks = data.chunks_exact(self.block_size);
+        for block in &mut blocks {
+            compress(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::md4::Md4;
+    use crate::md5::Md5;
+    use crate::sha1::Sha1;
+    use crate::sha2::{Sha256, Sha512};
+
+    fn from_digest_should_continue_after_the_padding<H: MerkleDamgard>() {
+        let message = b"a message of some length";
+        let glued = [&message[..], &H::padding(message.len() as u64)].concat();
+        let mut continued = H::from_digest(&H::digest(message), glued.len() as u64).unwrap();
+        continued.update(b"more");
+        assert_eq!(
+            continued.finalize(),
+            H::digest(&[&glued[..], b"more"].concat())
+        );
+        assert!(H::from_digest(&H::digest(message)[1..], glued.len() as u64).is_err());
+    }
+
+    #[test]
+    fn from_digest_should_continue_after_the_padding_for_every_hash() {
+        from_digest_should_continue_after_the_padding::<Md4>();
+        from_digest_should_continue_after_the_padding::<Md5>();
+        from_digest_should_continue_after_the_padding::<Sha1>();
+        from_digest_should_continue_after_the_padding::<Sha256>();
+        from_digest_should_continue_after_the_padding::<Sha512>();
+    }
+}
@@ -0,0 +1,295 @@
+//! The MT19937 Mersenne Twister, in its 32-bit and 64-bit variants,
+//! and attacks on it (cryptopals challenges 21 to 24).
+//!
+//! Every output is a state word put through an invertible tempering function,
+//! so untempering as many outputs as there are state words gives the whole state,
+//! and a clone of the generator predicts all its future outputs.
+//! A generator seeded from a small space, e.g. a recent timestamp or a 16-bit key,
+//! is broken by trying every seed.
+
+use crate::error::{Error, Result};
+
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX: u32 = 0x9908_b0df;
+const UPPER: u32 = 0x8000_0000;
+const LOWER: u32 = 0x7fff_ffff;
+
+const N_64: usize = 312;
+const M_64: usize = 156;
+const MATRIX_64: u64 = 0xb502_6f5a_a966_19e9;
+const UPPER_64: u64 = 0xffff_ffff_8000_0000;
+const LOWER_64: u64 = 0x7fff_ffff;
+
+/// The 32-bit Mersenne Twister.
+/// The state is twisted a word at a time, as the words are used,
+/// which gives the same outputs as twisting all of it at once.
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    /// ```
+    /// let mut mt = cryptopals::mt19937::Mt19937::new(5489);
+    /// assert_eq!(mt.next_u32(), 3_499_211_612);
+    /// ```
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0; N];
+        state[0] = seed;
+        for i in 1..N {
+            state[i] = 1_812_433_253u32
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { state, index: N }
+    }
+
+    /// Clones a generator from its last 624 (or more) consecutive outputs.
+    /// The clone goes on with the outputs which follow them.
+    pub fn from_outputs(outputs: &[u32]) -> Result<Self> {
+        if outputs.len() < N {
+            return Err(Error::LengthMismatch {
+                expected: N,
+                actual: outputs.len(),
+            });
+        }
+        let mut state = [0; N];
+        state
+            .iter_mut()
+            .zip(&outputs[outputs.len() - N..])
+            .for_each(|(word, output)| *word = untemper(*output));
+        Ok(Mt19937 { state, index: N })
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index == N {
+            self.index = 0;
+        }
+        let i = self.index;
+        let y = (self.state[i] & UPPER) | (self.state[(i + 1) % N] & LOWER);
+        let mut word = self.state[(i + M) % N] ^ (y >> 1);
+        if y & 1 == 1 {
+            word ^= MATRIX;
+        }
+        self.state[i] = word;
+        self.index += 1;
+        temper(word)
+    }
+}
+
+/// The 64-bit Mersenne Twister.
+pub struct Mt19937_64 {
+    state: [u64; N_64],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    /// ```
+    /// let mut mt = cryptopals::mt19937::Mt19937_64::new(5489);
+    /// assert_eq!(mt.next_u64(), 14_514_284_786_278_117_030);
+    /// ```
+    pub fn new(seed: u64) -> Self {
+        let mut state = [0; N_64];
+        state[0] = seed;
+        for i in 1..N_64 {
+            state[i] = 6_364_136_223_846_793_005u64
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 62))
+                .wrapping_add(i as u64);
+        }
+        Mt19937_64 { state, index: N_64 }
+    }
+
+    /// Clones a generator from its last 312 (or more) consecutive outputs.
+    pub fn from_outputs(outputs: &[u64]) -> Result<Self> {
+        if outputs.len() < N_64 {
+            return Err(Error::LengthMismatch {
+                expected: N_64,
+                actual: outputs.len(),
+            });
+        }
+        let mut state = [0; N_64];
+        state
+            .iter_mut()
+            .zip(&outputs[outputs.len() - N_64..])
+            .for_each(|(word, output)| *word = untemper_64(*output));
+        Ok(Mt19937_64 { state, index: N_64 })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        if self.index == N_64 {
+            self.index = 0;
+        }
+        let i = self.index;
+        let y = (self.state[i] & UPPER_64) | (self.state[(i + 1) % N_64] & LOWER_64);
+        let mut word = self.state[(i + M_64) % N_64] ^ (y >> 1);
+        if y & 1 == 1 {
+            word ^= MATRIX_64;
+        }
+        self.state[i] = word;
+        self.index += 1;
+        temper_64(word)
+    }
+}
+
+pub fn temper(word: u32) -> u32 {
+    let mut y = word;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c_5680;
+    y ^= (y << 15) & 0xefc6_0000;
+    y ^ (y >> 18)
+}
+
+/// Inverts temper, giving the state word behind an output.
+/// ```
+/// use cryptopals::mt19937::{temper, untemper};
+/// assert_eq!(untemper(temper(0xdead_beef)), 0xdead_beef);
+/// ```
+pub fn untemper(output: u32) -> u32 {
+    let y = undo_right_shift(output as u64, 18, 32);
+    let y = undo_left_shift(y, 15, 0xefc6_0000, 32);
+    let y = undo_left_shift(y, 7, 0x9d2c_5680, 32);
+    undo_right_shift(y, 11, 32) as u32
+}
+
+pub fn temper_64(word: u64) -> u64 {
+    let mut y = word;
+    y ^= (y >> 29) & 0x5555_5555_5555_5555;
+    y ^= (y << 17) & 0x71d6_7fff_eda6_0000;
+    y ^= (y << 37) & 0xfff7_eee0_0000_0000;
+    y ^ (y >> 43)
+}
+
+/// Inverts temper_64.
+pub fn untemper_64(output: u64) -> u64 {
+    let y = undo_right_shift(output, 43, 64);
+    let y = undo_left_shift(y, 37, 0xfff7_eee0_0000_0000, 64);
+    let y = undo_left_shift(y, 17, 0x71d6_7fff_eda6_0000, 64);
+    undo_masked_right_shift(y, 29, 0x5555_5555_5555_5555, 64)
+}
+
+/// Inverts y ^ (y >> shift) of a word of the given number of bits.
+fn undo_right_shift(value: u64, shift: u32, bits: u32) -> u64 {
+    undo_masked_right_shift(value, shift, u64::MAX, bits)
+}
+
+/// Inverts y ^ ((y >> shift) & mask): every round fixes shift more of the high bits.
+fn undo_masked_right_shift(value: u64, shift: u32, mask: u64, bits: u32) -> u64 {
+    let mut y = value;
+    for _ in 0..bits / shift {
+        y = value ^ ((y >> shift) & mask);
+    }
+    y
+}
+
+/// Inverts y ^ ((y << shift) & mask): every round fixes shift more of the low bits.
+fn undo_left_shift(value: u64, shift: u32, mask: u64, bits: u32) -> u64 {
+    let width = if bits == 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    };
+    let mut y = value;
+    for _ in 0..bits / shift {
+        y = (value ^ ((y << shift) & mask)) & width;
+    }
+    y
+}
+
+/// Finds the timestamp, between now minus window and now, which seeded
+/// a generator that produced the outputs first. The latest timestamps are tried first.
+pub fn recover_time_seed(outputs: &[u32], now: u32, window: u32) -> Option<u32> {
+    (now.saturating_sub(window)..=now).rev().find(|seed| {
+        let mut mt = Mt19937::new(*seed);
+        outputs.iter().all(|output| mt.next_u32() == *output)
+    })
+}
+
+/// Encrypts or decrypts with the MT19937 stream cipher: the data is XOR'ed with
+/// the low bytes of the outputs of a generator seeded with the 16-bit key.
+/// ```
+/// use cryptopals::mt19937::stream;
+/// assert_eq!(stream(&stream(b"attack at dawn", 1234), 1234), b"attack at dawn".to_vec());
+/// ```
+pub fn stream(data: &[u8], key: u16) -> Vec<u8> {
+    let mut mt = Mt19937::new(key as u32);
+    data.iter().map(|b| b ^ mt.next_u32() as u8).collect()
+}
+
+/// Recovers the key of the MT19937 stream cipher from a ciphertext whose plaintext
+/// ends with known_suffix, trying all the 2^16 keys.
+pub fn recover_stream_key(ciphertext: &[u8], known_suffix: &[u8]) -> Result<u16> {
+    if known_suffix.is_empty() || known_suffix.len() > ciphertext.len() {
+        return Err(Error::Cryptanalysis(
+            "need a known suffix no longer than the ciphertext".to_owned(),
+        ));
+    }
+    let offset = ciphertext.len() - known_suffix.len();
+    let keystream: Vec<u8> = ciphertext[offset..]
+        .iter()
+        .zip(known_suffix)
+        .map(|(c, p)| c ^ p)
+        .collect();
+    (0..=u16::MAX)
+        .find(|key| {
+            let mut mt = Mt19937::new(*key as u32);
+            (0..offset).for_each(|_| {
+                mt.next_u32();
+            });
+            keystream.iter().all(|k| mt.next_u32() as u8 == *k)
+        })
+        .ok_or_else(|| Error::Cryptanalysis("no key gives the known plaintext".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    #[test]
+    fn new_should_match_the_reference_outputs() {
+        let mut mt = Mt19937::new(5489);
+        let outputs: Vec<u32> = (0..10000).map(|_| mt.next_u32()).collect();
+        assert_eq!(outputs[..3], [3_499_211_612, 581_869_302, 3_890_346_734]);
+        assert_eq!(outputs[9999], 4_123_659_995);
+
+        let mut mt = Mt19937_64::new(5489);
+        let last = (0..10000).map(|_| mt.next_u64()).last();
+        assert_eq!(last, Some(9_981_545_732_273_789_042));
+    }
+
+    #[test]
+    fn from_outputs_should_predict_the_following_outputs() {
+        let mut mt = Mt19937::new(Random::with_seed(23).next_u64() as u32);
+        let outputs: Vec<u32> = (0..1000).map(|_| mt.next_u32()).collect();
+        let mut clone = Mt19937::from_outputs(&outputs).unwrap();
+        assert!((0..2000).all(|_| clone.next_u32() == mt.next_u32()));
+        assert!(Mt19937::from_outputs(&outputs[..623]).is_err());
+
+        let mut mt = Mt19937_64::new(Random::with_seed(64).next_u64());
+        let outputs: Vec<u64> = (0..N_64).map(|_| mt.next_u64()).collect();
+        let mut clone = Mt19937_64::from_outputs(&outputs).unwrap();
+        assert!((0..1000).all(|_| clone.next_u64() == mt.next_u64()));
+    }
+
+    #[test]
+    fn recover_time_seed_should_find_a_recent_timestamp() {
+        let now = 1_700_000_000;
+        let seed = now - 700;
+        let mut mt = Mt19937::new(seed);
+        let outputs = [mt.next_u32(), mt.next_u32()];
+        assert_eq!(recover_time_seed(&outputs, now, 1000), Some(seed));
+        assert_eq!(recover_time_seed(&outputs, now, 500), None);
+    }
+
+    #[test]
+    fn recover_stream_key_should_find_the_key_from_known_plaintext() {
+        let mut random = Random::with_seed(24);
+        let key = random.next_u64() as u16;
+        let length = random.range(5, 20);
+        let mut plaintext = random.bytes(length);
+        plaintext.extend_from_slice(&[b'A'; 14]);
+        let ciphertext = stream(&plaintext, key);
+        assert_eq!(recover_stream_key(&ciphertext, &[b'A'; 14]), Ok(key));
+    }
+}
@@ -0,0 +1,507 @@
+//! Oracles: the black boxes which the attacks of the crate query.
+//!
+//! An oracle encrypts, decrypts, edits a ciphertext, or tells whether a ciphertext
+//! is validly padded, or a MAC is valid, under a key the attacker does not know.
+//! Wrappers around an oracle count the queries made to it, enforce a budget of queries,
+//! slow it down, make its answers unreliable, or record a transcript of the queries.
+//!
+//! Oracles take `&self`, so that attacks can query them from several threads,
+//! and the traits are implemented for references, so that attacks can wrap
+//! a borrowed oracle, e.g. in `Counting`, to report the queries they made.
+
+use crate::error::{Error, Result};
+use crate::random::Random;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Encrypts chosen plaintexts, usually along with data the attacker does not know.
+pub trait EncryptionOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Decrypts chosen ciphertexts.
+pub trait DecryptionOracle {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Tells whether a ciphertext decrypts to a validly padded plaintext.
+/// For chained modes, the first block of the ciphertext is the IV.
+pub trait PaddingOracle {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool>;
+}
+
+/// Tells whether a MAC (or signature) of a message is valid.
+pub trait MacOracle {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool>;
+}
+
+/// Replaces part of the plaintext of a ciphertext, returning the new ciphertext,
+/// like the random access write of an encrypted disk.
+pub trait EditOracle {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl<O: EncryptionOracle + ?Sized> EncryptionOracle for &O {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        (**self).encrypt(plaintext)
+    }
+}
+
+impl<O: DecryptionOracle + ?Sized> DecryptionOracle for &O {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        (**self).decrypt(ciphertext)
+    }
+}
+
+impl<O: PaddingOracle + ?Sized> PaddingOracle for &O {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        (**self).valid_padding(ciphertext)
+    }
+}
+
+impl<O: MacOracle + ?Sized> MacOracle for &O {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        (**self).verify(message, mac)
+    }
+}
+
+impl<O: EditOracle + ?Sized> EditOracle for &O {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        (**self).edit(ciphertext, offset, plaintext)
+    }
+}
+
+/// An oracle answering with a function, e.g. a closure.
+/// ```
+/// use cryptopals::oracle::{self, EncryptionOracle};
+/// let reverse = oracle::from_fn(|p: &[u8]| Ok(p.iter().rev().copied().collect()));
+/// assert_eq!(reverse.encrypt(b"abc"), Ok(b"cba".to_vec()));
+/// ```
+pub struct FromFn<F>(F);
+
+pub fn from_fn<F>(f: F) -> FromFn<F> {
+    FromFn(f)
+}
+
+impl<F: Fn(&[u8]) -> Result<Vec<u8>>> EncryptionOracle for FromFn<F> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        (self.0)(plaintext)
+    }
+}
+
+impl<F: Fn(&[u8]) -> Result<Vec<u8>>> DecryptionOracle for FromFn<F> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        (self.0)(ciphertext)
+    }
+}
+
+impl<F: Fn(&[u8]) -> Result<bool>> PaddingOracle for FromFn<F> {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        (self.0)(ciphertext)
+    }
+}
+
+impl<F: Fn(&[u8], &[u8]) -> Result<bool>> MacOracle for FromFn<F> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        (self.0)(message, mac)
+    }
+}
+
+impl<F: Fn(&[u8], usize, &[u8]) -> Result<Vec<u8>>> EditOracle for FromFn<F> {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        (self.0)(ciphertext, offset, plaintext)
+    }
+}
+
+/// Counts the queries made to the oracle.
+/// ```
+/// use cryptopals::oracle::{self, Counting, EncryptionOracle};
+/// let oracle = Counting::new(oracle::from_fn(|p: &[u8]| Ok(p.to_vec())));
+/// oracle.encrypt(b"a").unwrap();
+/// oracle.encrypt(b"b").unwrap();
+/// assert_eq!(oracle.queries(), 2);
+/// ```
+pub struct Counting<O> {
+    inner: O,
+    queries: AtomicU64,
+}
+
+impl<O> Counting<O> {
+    pub fn new(inner: O) -> Self {
+        Counting {
+            inner,
+            queries: AtomicU64::new(0),
+        }
+    }
+
+    pub fn queries(&self) -> u64 {
+        self.queries.load(Ordering::SeqCst)
+    }
+
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    fn count(&self) -> &O {
+        self.queries.fetch_add(1, Ordering::SeqCst);
+        &self.inner
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Counting<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.count().encrypt(plaintext)
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Counting<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.count().decrypt(ciphertext)
+    }
+}
+
+impl<O: PaddingOracle> PaddingOracle for Counting<O> {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        self.count().valid_padding(ciphertext)
+    }
+}
+
+impl<O: MacOracle> MacOracle for Counting<O> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        self.count().verify(message, mac)
+    }
+}
+
+impl<O: EditOracle> EditOracle for Counting<O> {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.count().edit(ciphertext, offset, plaintext)
+    }
+}
+
+/// Fails every query past a budget with `Error::BudgetExhausted`.
+pub struct Budget<O> {
+    inner: O,
+    budget: u64,
+    used: AtomicU64,
+}
+
+impl<O> Budget<O> {
+    pub fn new(inner: O, budget: u64) -> Self {
+        Budget {
+            inner,
+            budget,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.budget.saturating_sub(self.used.load(Ordering::SeqCst))
+    }
+
+    fn spend(&self) -> Result<&O> {
+        if self.used.fetch_add(1, Ordering::SeqCst) < self.budget {
+            Ok(&self.inner)
+        } else {
+            Err(Error::BudgetExhausted {
+                budget: self.budget,
+            })
+        }
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Budget<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.spend()?.encrypt(plaintext)
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Budget<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.spend()?.decrypt(ciphertext)
+    }
+}
+
+impl<O: PaddingOracle> PaddingOracle for Budget<O> {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        self.spend()?.valid_padding(ciphertext)
+    }
+}
+
+impl<O: MacOracle> MacOracle for Budget<O> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        self.spend()?.verify(message, mac)
+    }
+}
+
+impl<O: EditOracle> EditOracle for Budget<O> {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.spend()?.edit(ciphertext, offset, plaintext)
+    }
+}
+
+/// Sleeps before answering every query, like an oracle across a network.
+pub struct Latency<O> {
+    inner: O,
+    delay: Duration,
+}
+
+impl<O> Latency<O> {
+    pub fn new(inner: O, delay: Duration) -> Self {
+        Latency { inner, delay }
+    }
+
+    fn wait(&self) -> &O {
+        thread::sleep(self.delay);
+        &self.inner
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Latency<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.wait().encrypt(plaintext)
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Latency<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.wait().decrypt(ciphertext)
+    }
+}
+
+impl<O: PaddingOracle> PaddingOracle for Latency<O> {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        self.wait().valid_padding(ciphertext)
+    }
+}
+
+impl<O: MacOracle> MacOracle for Latency<O> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        self.wait().verify(message, mac)
+    }
+}
+
+impl<O: EditOracle> EditOracle for Latency<O> {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.wait().edit(ciphertext, offset, plaintext)
+    }
+}
+
+/// Flips the answers of a yes/no oracle at random, with a probability,
+/// like a side channel which is sometimes misread.
+pub struct Noisy<O> {
+    inner: O,
+    probability: f64,
+    random: Mutex<Random>,
+}
+
+impl<O> Noisy<O> {
+    pub fn new(inner: O, probability: f64, random: Random) -> Self {
+        Noisy {
+            inner,
+            probability,
+            random: Mutex::new(random),
+        }
+    }
+
+    fn distort(&self, answer: bool) -> bool {
+        let draw = self.random.lock().unwrap().next_u64() as f64 / u64::MAX as f64;
+        answer ^ (draw < self.probability)
+    }
+}
+
+impl<O: PaddingOracle> PaddingOracle for Noisy<O> {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        Ok(self.distort(self.inner.valid_padding(ciphertext)?))
+    }
+}
+
+impl<O: MacOracle> MacOracle for Noisy<O> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        Ok(self.distort(self.inner.verify(message, mac)?))
+    }
+}
+
+/// One query to an oracle and its answer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Exchange {
+    Encrypt {
+        plaintext: Vec<u8>,
+        result: Result<Vec<u8>>,
+    },
+    Decrypt {
+        ciphertext: Vec<u8>,
+        result: Result<Vec<u8>>,
+    },
+    Padding {
+        ciphertext: Vec<u8>,
+        result: Result<bool>,
+    },
+    Verify {
+        message: Vec<u8>,
+        mac: Vec<u8>,
+        result: Result<bool>,
+    },
+    Edit {
+        ciphertext: Vec<u8>,
+        offset: usize,
+        plaintext: Vec<u8>,
+        result: Result<Vec<u8>>,
+    },
+}
+
+/// Records every query made to the oracle, and its answer, in order.
+pub struct Transcript<O> {
+    inner: O,
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl<O> Transcript<O> {
+    pub fn new(inner: O) -> Self {
+        Transcript {
+            inner,
+            exchanges: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn exchanges(&self) -> Vec<Exchange> {
+        self.exchanges.lock().unwrap().clone()
+    }
+
+    fn record(&self, exchange: Exchange) {
+        self.exchanges.lock().unwrap().push(exchange);
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Transcript<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let result = self.inner.encrypt(plaintext);
+        self.record(Exchange::Encrypt {
+            plaintext: plaintext.to_vec(),
+            result: result.clone(),
+        });
+        result
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Transcript<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let result = self.inner.decrypt(ciphertext);
+        self.record(Exchange::Decrypt {
+            ciphertext: ciphertext.to_vec(),
+            result: result.clone(),
+        });
+        result
+    }
+}
+
+impl<O: PaddingOracle> PaddingOracle for Transcript<O> {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        let result = self.inner.valid_padding(ciphertext);
+        self.record(Exchange::Padding {
+            ciphertext: ciphertext.to_vec(),
+            result: result.clone(),
+        });
+        result
+    }
+}
+
+impl<O: MacOracle> MacOracle for Transcript<O> {
+    fn verify(&self, message: &[u8], mac: &[u8]) -> Result<bool> {
+        let result = self.inner.verify(message, mac);
+        self.record(Exchange::Verify {
+            message: message.to_vec(),
+            mac: mac.to_vec(),
+            result: result.clone(),
+        });
+        result
+    }
+}
+
+impl<O: EditOracle> EditOracle for Transcript<O> {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let result = self.inner.edit(ciphertext, offset, plaintext);
+        self.record(Exchange::Edit {
+            ciphertext: ciphertext.to_vec(),
+            offset,
+            plaintext: plaintext.to_vec(),
+            result: result.clone(),
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn odd_length() -> FromFn<impl Fn(&[u8]) -> Result<bool>> {
+        from_fn(|c: &[u8]| Ok(c.len() % 2 == 1))
+    }
+
+    #[test]
+    fn budget_should_fail_queries_past_the_budget() {
+        let oracle = Budget::new(odd_length(), 2);
+        assert_eq!(oracle.valid_padding(b"a"), Ok(true));
+        assert_eq!(oracle.valid_padding(b"ab"), Ok(false));
+        assert_eq!(oracle.remaining(), 0);
+        assert_eq!(
+            oracle.valid_padding(b"a"),
+            Err(Error::BudgetExhausted { budget: 2 })
+        );
+    }
+
+    #[test]
+    fn noisy_should_flip_about_the_given_fraction_of_answers() {
+        let oracle = Noisy::new(odd_length(), 0.25, Random::with_seed(3));
+        let flipped = (0..2000)
+            .filter(|_| !oracle.valid_padding(b"a").unwrap())
+            .count();
+        assert!((400..600).contains(&flipped), "flipped {}", flipped);
+    }
+
+    #[test]
+    fn wrappers_should_compose_over_borrowed_oracles() {
+        let inner = odd_length();
+        let transcript = Transcript::new(&inner);
+        let counting = Counting::new(&transcript);
+        counting.valid_padding(b"abc").unwrap();
+        counting.valid_padding(b"ab").unwrap();
+        assert_eq!(counting.queries(), 2);
+        assert_eq!(
+            transcript.exchanges()[1],
+            Exchange::Padding {
+                ciphertext: b"ab".to_vec(),
+                result: Ok(false)
+            }
+        );
+    }
+
+    #[test]
+    fn transcript_should_record_edits() {
+        let oracle = Transcript::new(from_fn(|c: &[u8], offset: usize, p: &[u8]| {
+            let mut edited = c.to_vec();
+            edited[offset..offset + p.len()].copy_from_slice(p);
+            Ok(edited)
+        }));
+        assert_eq!(oracle.edit(b"abcd", 1, b"xy"), Ok(b"axyd".to_vec()));
+        assert_eq!(
+            oracle.exchanges(),
+            vec![Exchange::Edit {
+                ciphertext: b"abcd".to_vec(),
+                offset: 1,
+                plaintext: b"xy".to_vec(),
+                result: Ok(b"axyd".to_vec())
+            }]
+        );
+    }
+
+    #[test]
+    fn latency_should_delay_answers() {
+        let oracle = Latency::new(odd_length(), Duration::from_millis(5));
+        let start = std::time::Instant::now();
+        oracle.valid_padding(b"a").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::error::{Error, Result};
+
+/// Pads the bytes to a multiple of block_size, as per PKCS#7:
+/// with N bytes of value N, where N is between 1 and block_size.
+///
+/// # Panics
+///
+/// Panics if block_size is not between 1 and 255, as N must fit in a byte.
+/// ```
+/// assert_eq!(
+///     cryptopals::padding::pkcs7_pad(b"YELLOW SUBMARINE", 20),
+///     b"YELLOW SUBMARINE\x04\x04\x04\x04".to_vec());
+/// ```
+pub fn pkcs7_pad(bytes: &[u8], block_size: usize) -> Vec<u8> {
+    assert!(
+        (1..=255).contains(&block_size),
+        "PKCS#7 block size must be between 1 and 255, got {}",
+        block_size
+    );
+    let pad = block_size - bytes.len() % block_size;
+    let mut padded = bytes.to_vec();
+    padded.extend(std::iter::repeat_n(pad as u8, pad));
+    padded
+}
+
+/// Strips PKCS#7 padding from the bytes.
+/// Fails if the bytes are not validly padded to a multiple of block_size.
+/// ```
+/// use cryptopals::error::Error;
+/// assert_eq!(
+///     cryptopals::padding::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16),
+///     Ok(b"ICE ICE BABY".to_vec()));
+/// assert_eq!(
+///     cryptopals::padding::pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+///     Err(Error::Padding));
+/// ```
+pub fn pkcs7_unpad(bytes: &[u8], block_size: usize) -> Result<Vec<u8>> {
+    let pad = match bytes.last() {
+        Some(pad) if bytes.len().is_multiple_of(block_size) => *pad as usize,
+        _ => return Err(Error::Padding),
+    };
+    if pad == 0
+        || pad > block_size
+        || bytes[bytes.len() - pad..]
+            .iter()
+            .any(|b| *b as usize != pad)
+    {
+        return Err(Error::Padding);
+    }
+    Ok(bytes[..bytes.len() - pad].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkcs7_pad_should_add_a_full_block_to_aligned_input() {
+        assert_eq!(pkcs7_pad(&[1, 2, 3, 4], 4), vec![1, 2, 3, 4, 4, 4, 4, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pkcs7_pad_should_panic_for_an_empty_block_size() {
+        pkcs7_pad(&[1, 2, 3], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pkcs7_pad_should_panic_for_a_block_size_over_255() {
+        pkcs7_pad(&[1, 2, 3], 256);
+    }
+
+    #[test]
+    fn pkcs7_unpad_should_reverse_pkcs7_pad() {
+        let bytes = b"some bytes of odd length".to_vec();
+        assert_eq!(pkcs7_unpad(&pkcs7_pad(&bytes, 16), 16), Ok(bytes));
+    }
+
+    #[test]
+    fn pkcs7_unpad_should_reject_invalid_padding() {
+        assert_eq!(pkcs7_unpad(&[1, 2, 3, 0], 4), Err(Error::Padding));
+        assert_eq!(pkcs7_unpad(&[1, 2, 3, 5], 4), Err(Error::Padding));
+        assert_eq!(pkcs7_unpad(&[1, 2, 3, 2], 4), Err(Error::Padding));
+        assert_eq!(pkcs7_unpad(&[1, 2, 1], 4), Err(Error::Padding));
+        assert_eq!(pkcs7_unpad(&[], 4), Err(Error::Padding));
+    }
+}
@@ -0,0 +1,332 @@
+//! The CBC padding oracle attack.
+//!
+//! CBC decrypts a block C into D(C) XOR the previous ciphertext block (the IV for the first).
+//! Whoever can choose the previous block, and learn whether the result is validly padded,
+//! can find D(C) a byte at a time: the last byte of D(C) XOR the chosen block is 0x01
+//! for one of the 256 choices of its last byte; with the last byte then set to give 0x02,
+//! the last but one byte of D(C) XOR the chosen block is 0x02 for one choice, and so on.
+//! D(C) XOR the real previous block is the plaintext, and D(C) XOR a plaintext block is
+//! the previous block which makes C decrypt to that plaintext, which lets the oracle
+//! encrypt too (CBC-R).
+
+use crate::aes128::modes::{self, BLOCK_SIZE};
+use crate::error::{Error, Result};
+use crate::hexaa;
+use crate::oracle::{Counting, PaddingOracle};
+use crate::padding;
+use crate::random::Random;
+use std::thread;
+
+/// Encrypts messages with a random IV, and tells whether ciphertexts are validly padded.
+pub struct PaddingOracleService {
+    key: u128,
+}
+
+impl PaddingOracleService {
+    pub fn new(random: &mut Random) -> Self {
+        PaddingOracleService {
+            key: random.next_u128(),
+        }
+    }
+
+    /// Encrypts the plaintext in CBC mode, returning the IV followed by the ciphertext.
+    pub fn encrypt(&self, plaintext: &[u8], iv: u128) -> Vec<u8> {
+        let mut encrypted = hexaa::u128_to_bytes(iv);
+        encrypted.extend(modes::cbc_encrypt(plaintext, self.key, iv));
+        encrypted
+    }
+
+    /// Decrypts the IV and ciphertext, as returned by encrypt.
+    pub fn decrypt(&self, iv_and_ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if iv_and_ciphertext.len() < BLOCK_SIZE {
+            return Err(Error::LengthMismatch {
+                expected: BLOCK_SIZE,
+                actual: iv_and_ciphertext.len(),
+            });
+        }
+        let (iv, ciphertext) = iv_and_ciphertext.split_at(BLOCK_SIZE);
+        modes::cbc_decrypt(ciphertext, self.key, hexaa::bytes_to_u128(iv))
+    }
+}
+
+impl PaddingOracle for PaddingOracleService {
+    fn valid_padding(&self, ciphertext: &[u8]) -> Result<bool> {
+        match self.decrypt(ciphertext) {
+            Ok(_) => Ok(true),
+            Err(Error::Padding) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Parameters of the attack.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Attack the blocks in as many threads as the machine runs in parallel.
+    pub parallel: bool,
+    /// Number of queries whose majority must confirm a valid padding,
+    /// to tolerate an oracle which answers wrongly at times. 1 trusts the oracle.
+    pub votes: usize,
+    /// Number of times to scan all the values of a byte again, when none gave a valid padding.
+    pub retries: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            parallel: true,
+            votes: 1,
+            retries: 2,
+        }
+    }
+}
+
+/// A ciphertext decrypted with the oracle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decryption {
+    /// The plaintext, with its padding.
+    pub padded: Vec<u8>,
+    /// Number of queries made to decrypt each block.
+    pub queries_per_block: Vec<u64>,
+}
+
+impl Decryption {
+    /// The plaintext, without its padding.
+    pub fn plaintext(&self) -> Result<Vec<u8>> {
+        padding::pkcs7_unpad(&self.padded, BLOCK_SIZE)
+    }
+
+    pub fn queries(&self) -> u64 {
+        self.queries_per_block.iter().sum()
+    }
+}
+
+/// A ciphertext forged with the oracle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Encryption {
+    /// The IV followed by the ciphertext.
+    pub iv_and_ciphertext: Vec<u8>,
+    /// Number of queries made to forge each block, from the IV on.
+    pub queries_per_block: Vec<u64>,
+}
+
+/// Decrypts the ciphertext, which starts with its IV, with the padding oracle.
+pub fn decrypt<O>(oracle: &O, iv_and_ciphertext: &[u8], config: &Config) -> Result<Decryption>
+where
+    O: PaddingOracle + Sync,
+{
+    if iv_and_ciphertext.len() < 2 * BLOCK_SIZE
+        || !iv_and_ciphertext.len().is_multiple_of(BLOCK_SIZE)
+    {
+        return Err(Error::LengthMismatch {
+            expected: (iv_and_ciphertext.len() / BLOCK_SIZE).max(2) * BLOCK_SIZE,
+            actual: iv_and_ciphertext.len(),
+        });
+    }
+    let blocks: Vec<&[u8]> = iv_and_ciphertext.chunks_exact(BLOCK_SIZE).collect();
+    let decrypt_block = |i: usize| -> Result<(Vec<u8>, u64)> {
+        let oracle = Counting::new(oracle);
+        let intermediate = intermediate(&oracle, blocks[i], config)?;
+        Ok((
+            hexaa::xor_bytes(intermediate, blocks[i - 1]),
+            oracle.queries(),
+        ))
+    };
+    let results: Vec<Result<(Vec<u8>, u64)>> = if config.parallel {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let indices: Vec<usize> = (1..blocks.len()).collect();
+        let decrypt_block = &decrypt_block;
+        thread::scope(|scope| {
+            let handles: Vec<_> = indices
+                .chunks(indices.len().div_ceil(threads))
+                .map(|chunk| scope.spawn(move || chunk.iter().map(|&i| decrypt_block(i)).collect()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| -> Vec<_> { handle.join().expect("block decryption panicked") })
+                .collect()
+        })
+    } else {
+        (1..blocks.len()).map(decrypt_block).collect()
+    };
+
+    let mut decryption = Decryption {
+        padded: vec![],
+        queries_per_block: vec![],
+    };
+    for result in results {
+        let (plaintext, queries) = result?;
+        decryption.padded.extend(plaintext);
+        decryption.queries_per_block.push(queries);
+    }
+    Ok(decryption)
+}
+
+/// Forges a ciphertext of the plaintext with the padding oracle (CBC-R),
+/// working back from a last ciphertext block of the attacker's choice.
+/// Every block depends on the next, so the blocks are forged one after another.
+pub fn encrypt<O: PaddingOracle>(
+    oracle: &O,
+    plaintext: &[u8],
+    last_block: u128,
+    config: &Config,
+) -> Result<Encryption> {
+    let padded = padding::pkcs7_pad(plaintext, BLOCK_SIZE);
+    let mut blocks = vec![hexaa::u128_to_bytes(last_block)];
+    let mut queries_per_block = vec![];
+    for plaintext_block in padded.chunks_exact(BLOCK_SIZE).rev() {
+        let oracle = Counting::new(oracle);
+        let intermediate = intermediate(&oracle, &blocks[0], config)?;
+        blocks.insert(0, hexaa::xor_bytes(intermediate, plaintext_block));
+        queries_per_block.insert(0, oracle.queries());
+    }
+    Ok(Encryption {
+        iv_and_ciphertext: blocks.concat(),
+        queries_per_block,
+    })
+}
+
+/// Finds D(block), the block decrypted before it is XOR'ed with the previous block,
+/// from its last byte to its first.
+fn intermediate<O: PaddingOracle>(oracle: &O, block: &[u8], config: &Config) -> Result<Vec<u8>> {
+    if config.votes == 0 {
+        return Err(Error::Cryptanalysis(
+            "at least one vote is needed to confirm a padding".to_owned(),
+        ));
+    }
+    let mut intermediate = vec![0; BLOCK_SIZE];
+    for position in (0..BLOCK_SIZE).rev() {
+        let mut found = None;
+        for _ in 0..=config.retries {
+            found = find_byte(oracle, block, &intermediate, position, config)?;
+            if found.is_some() {
+                break;
+            }
+        }
+        intermediate[position] = found.ok_or_else(|| {
+            Error::Cryptanalysis(format!(
+                "no value of byte {} gives a valid padding",
+                position
+            ))
+        })?;
+    }
+    Ok(intermediate)
+}
+
+/// Finds the byte at position of D(block), given the bytes after it.
+fn find_byte<O: PaddingOracle>(
+    oracle: &O,
+    block: &[u8],
+    intermediate: &[u8],
+    position: usize,
+    config: &Config,
+) -> Result<Option<u8>> {
+    let pad = (BLOCK_SIZE - position) as u8;
+    let mut query = vec![0; 2 * BLOCK_SIZE];
+    (position + 1..BLOCK_SIZE).for_each(|i| query[i] = intermediate[i] ^ pad);
+    query[BLOCK_SIZE..].copy_from_slice(block);
+    for guess in 0..=255 {
+        query[position] = guess;
+        if !oracle.valid_padding(&query)? {
+            continue;
+        }
+        if config.votes > 1 && !confirm(oracle, &query, config.votes)? {
+            continue;
+        }
+        // the last byte may have made a longer padding valid, e.g. \x02\x02;
+        // changing the byte before it only spoils a longer padding
+        if pad == 1 {
+            let mut check = query.clone();
+            check[position - 1] ^= 0xff;
+            if !confirm(oracle, &check, config.votes)? {
+                continue;
+            }
+        }
+        return Ok(Some(guess ^ pad));
+    }
+    Ok(None)
+}
+
+/// Whether the majority of the given number of queries say the padding is valid.
+fn confirm<O: PaddingOracle>(oracle: &O, query: &[u8], votes: usize) -> Result<bool> {
+    let mut valid = 0;
+    for _ in 0..votes {
+        if oracle.valid_padding(query)? {
+            valid += 1;
+        }
+    }
+    Ok(2 * valid > votes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::Noisy;
+
+    #[test]
+    fn decrypt_should_recover_all_blocks_including_the_first() {
+        let mut random = Random::with_seed(17);
+        let service = PaddingOracleService::new(&mut random);
+        let plaintext = b"000001With the bass kicked in and the Vega's are pumpin'";
+        let ciphertext = service.encrypt(plaintext, random.next_u128());
+
+        let decryption = decrypt(&service, &ciphertext, &Config::default()).unwrap();
+        assert_eq!(decryption.plaintext(), Ok(plaintext.to_vec()));
+        assert_eq!(decryption.queries_per_block.len(), 4);
+        assert!(decryption.queries_per_block.iter().all(|q| *q >= 16));
+    }
+
+    #[test]
+    fn decrypt_should_not_be_fooled_by_longer_paddings() {
+        // the last byte decrypts to 0x02 when the byte before it is 0x02 too
+        let mut random = Random::with_seed(3);
+        let service = PaddingOracleService::new(&mut random);
+        let ciphertext = service.encrypt(b"fourteen bytes\x02", 0);
+        let config = Config {
+            parallel: false,
+            ..Config::default()
+        };
+        let decryption = decrypt(&service, &ciphertext, &config).unwrap();
+        assert_eq!(decryption.plaintext(), Ok(b"fourteen bytes\x02".to_vec()));
+    }
+
+    #[test]
+    fn decrypt_should_tolerate_a_noisy_oracle() {
+        let mut random = Random::with_seed(5);
+        let service = PaddingOracleService::new(&mut random);
+        let ciphertext = service.encrypt(b"noisy", 9);
+        let noisy = Noisy::new(&service, 0.01, Random::with_seed(6));
+        let config = Config {
+            votes: 5,
+            retries: 5,
+            ..Config::default()
+        };
+        let decryption = decrypt(&noisy, &ciphertext, &config).unwrap();
+        assert_eq!(decryption.plaintext(), Ok(b"noisy".to_vec()));
+    }
+
+    #[test]
+    fn decrypt_should_reject_zero_votes() {
+        let service = PaddingOracleService::new(&mut Random::with_seed(4));
+        let ciphertext = service.encrypt(b"votes", 0);
+        let config = Config {
+            votes: 0,
+            ..Config::default()
+        };
+        assert!(matches!(
+            decrypt(&service, &ciphertext, &config),
+            Err(Error::Cryptanalysis(message)) if message.contains("vote")
+        ));
+    }
+
+    #[test]
+    fn encrypt_should_forge_a_valid_ciphertext() {
+        let service = PaddingOracleService::new(&mut Random::with_seed(8));
+        let encryption = encrypt(&service, b";admin=true", 0x1234, &Config::default()).unwrap();
+        assert_eq!(encryption.queries_per_block.len(), 1);
+        assert_eq!(
+            service.decrypt(&encryption.iv_and_ciphertext),
+            Ok(b";admin=true".to_vec())
+        );
+    }
+}
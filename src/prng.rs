@@ -0,0 +1,685 @@
+//! Common non-cryptographic generators, and solvers which recover their state
+//! from observed outputs and predict the values to come.
+//!
+//! - java.util.Random, MSVC `rand` and PCG32 are linear congruential generators
+//!   modulo a power of two which only output the high bits of their state. Two outputs
+//!   pin the unknown low bits of a state down to the few which map, under the multiplier,
+//!   close to a known value, which is found without trying them all.
+//! - glibc `rand` and V8's xorshift128+ are linear over GF(2), but for the carries of
+//!   glibc's additions, which leak the bits its outputs drop. Their states are solved
+//!   as systems of linear equations in the bits of the state.
+
+use crate::error::{Error, Result};
+
+/// A linear congruential generator modulo 2^bits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Lcg {
+    multiplier: u64,
+    increment: u64,
+    bits: u32,
+    state: u64,
+}
+
+impl Lcg {
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.bits)
+    }
+
+    fn step(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(self.multiplier)
+            .wrapping_add(self.increment)
+            & self.mask();
+        self.state
+    }
+
+    /// The states whose bits from low_bits up are high, and whose next states'
+    /// bits from low_bits up are next_high.
+    fn candidates(&self, high: u64, next_high: u64, low_bits: u32) -> Vec<u64> {
+        // next = a * (high + low) + c, so a * low = next_high + next_low - a * high - c,
+        // where low and next_low are both below 2^low_bits
+        let modulus = 1u128 << self.bits;
+        let target = next_high
+            .wrapping_sub(high.wrapping_mul(self.multiplier))
+            .wrapping_sub(self.increment)
+            & self.mask();
+        let width = 1u128 << low_bits;
+        solutions(
+            self.multiplier as u128 % modulus,
+            modulus,
+            target as u128,
+            width,
+            width,
+        )
+        .into_iter()
+        .map(|low| high | low as u64)
+        .collect()
+    }
+}
+
+/// The numbers x below limit for which a * x mod m is in the range of the given width
+/// starting at low, which may wrap around m, smallest first.
+fn solutions(a: u128, m: u128, low: u128, width: u128, limit: u128) -> Vec<u128> {
+    let mut solutions = vec![];
+    let mut start = 0;
+    while start < limit {
+        // x = start + y, where a * y is in the range moved down by a * start
+        let low = (low + m - a * start % m) % m;
+        let y = if low + width > m {
+            // the range wraps around to include 0
+            Some(0)
+        } else {
+            min_in_range(a, m, low, low + width - 1)
+        };
+        match y {
+            Some(y) if start + y < limit => {
+                solutions.push(start + y);
+                start += y + 1;
+            }
+            _ => break,
+        }
+    }
+    solutions
+}
+
+/// The smallest x for which a * x mod m is between low and high, both included,
+/// found with a Euclid-like recursion on (m mod a, a).
+fn min_in_range(a: u128, m: u128, low: u128, high: u128) -> Option<u128> {
+    if low == 0 {
+        return Some(0);
+    }
+    let a = a % m;
+    if a == 0 {
+        return None;
+    }
+    let x = low.div_ceil(a);
+    if a * x <= high {
+        return Some(x);
+    }
+    // no multiple of a in [low, high]: look for the smallest y for which
+    // [m * y + low, m * y + high] has one, i.e. for which m * y mod a
+    // is in [-high mod a, -low mod a]
+    let y = min_in_range(m % a, a, a - high % a, a - low % a)?;
+    let x = m / a * y + ((m % a) * y + low).div_ceil(a);
+    let value = a.wrapping_mul(x).wrapping_sub(m.wrapping_mul(y));
+    if value <= high {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+/// Solves a system of linear equations over GF(2), in at most 128 variables.
+/// Every equation is a mask of the variables it sums, and the value of the sum.
+/// None if the equations are inconsistent or do not determine every variable.
+fn solve_gf2(equations: &[(u128, bool)], variables: u32) -> Option<u128> {
+    // pivots[i] is an equation whose highest variable is i
+    let mut pivots: Vec<Option<(u128, bool)>> = vec![None; variables as usize];
+    for (mask, value) in equations {
+        let (mut mask, mut value) = (*mask, *value);
+        while mask != 0 {
+            let highest = 127 - mask.leading_zeros() as usize;
+            match pivots[highest] {
+                Some((pivot_mask, pivot_value)) => {
+                    mask ^= pivot_mask;
+                    value ^= pivot_value;
+                }
+                None => {
+                    pivots[highest] = Some((mask, value));
+                    break;
+                }
+            }
+        }
+        if mask == 0 && value {
+            return None;
+        }
+    }
+    let mut solution = 0u128;
+    for (i, pivot) in pivots.iter().enumerate() {
+        let (mask, value) = (*pivot)?;
+        let rest = (mask & !(1 << i) & solution).count_ones() % 2 == 1;
+        if value ^ rest {
+            solution |= 1 << i;
+        }
+    }
+    Some(solution)
+}
+
+/// Checks the candidates against the outputs, and returns the only one which matches.
+fn single<T, F>(candidates: Vec<T>, matches: F) -> Result<T>
+where
+    F: Fn(&T) -> bool,
+{
+    let mut matching = candidates.into_iter().filter(|c| matches(c));
+    match (matching.next(), matching.next()) {
+        (Some(found), None) => Ok(found),
+        (None, _) => Err(Error::Cryptanalysis(
+            "no state produces the outputs".to_owned(),
+        )),
+        (Some(_), Some(_)) => Err(Error::Cryptanalysis(
+            "several states produce the outputs, more are needed".to_owned(),
+        )),
+    }
+}
+
+/// java.util.Random: a 48-bit LCG whose outputs are the high bits of the state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JavaRandom {
+    lcg: Lcg,
+}
+
+impl JavaRandom {
+    const MULTIPLIER: u64 = 0x5_deec_e66d;
+
+    /// `new Random(seed)`.
+    /// ```
+    /// let mut random = cryptopals::prng::JavaRandom::new(0);
+    /// assert_eq!(random.next_int(), -1_155_484_576);
+    /// ```
+    pub fn new(seed: i64) -> Self {
+        Self::with_state(seed as u64 ^ Self::MULTIPLIER)
+    }
+
+    fn with_state(state: u64) -> Self {
+        JavaRandom {
+            lcg: Lcg {
+                multiplier: Self::MULTIPLIER,
+                increment: 0xb,
+                bits: 48,
+                state: state & ((1 << 48) - 1),
+            },
+        }
+    }
+
+    /// The protected `next(bits)`, the top bits of the next state.
+    ///
+    /// # Panics
+    ///
+    /// If bits is over 32, which Java's `int` result cannot hold either.
+    pub fn next(&mut self, bits: u32) -> u32 {
+        assert!(bits <= 32, "at most 32 bits fit the result");
+        (self.lcg.step() >> (48 - bits)) as u32
+    }
+
+    pub fn next_int(&mut self) -> i32 {
+        self.next(32) as i32
+    }
+
+    /// `nextInt(bound)`: a number in [0, bound).
+    ///
+    /// # Panics
+    ///
+    /// If bound is not positive, where Java throws `IllegalArgumentException`.
+    pub fn next_int_bounded(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "bound must be positive");
+        if bound & -bound == bound {
+            return ((bound as i64 * self.next(31) as i64) >> 31) as i32;
+        }
+        loop {
+            let bits = self.next(31) as i32;
+            let value = bits % bound;
+            if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+                return value;
+            }
+        }
+    }
+
+    pub fn next_long(&mut self) -> i64 {
+        ((self.next_int() as i64) << 32).wrapping_add(self.next_int() as i64)
+    }
+
+    /// Recovers the generator from consecutive outputs of `next(bits)`, e.g. of `nextInt()`
+    /// (bits 32, as u32) or of `nextInt(bound)` with a power of two bound.
+    /// The recovered generator goes on with the outputs which follow them.
+    pub fn from_outputs(outputs: &[u32], bits: u32) -> Result<Self> {
+        if outputs.len() < 2 || bits == 0 || bits > 32 {
+            return Err(Error::Cryptanalysis(
+                "need at least two outputs of 1 to 32 bits".to_owned(),
+            ));
+        }
+        let low_bits = 48 - bits;
+        let high = |output: u32| (output as u64) << low_bits;
+        let lcg = Self::with_state(0).lcg;
+        let state = single(
+            lcg.candidates(high(outputs[0]), high(outputs[1]), low_bits),
+            |state| {
+                let mut random = Self::with_state(*state);
+                outputs[1..]
+                    .iter()
+                    .all(|output| random.next(bits) == *output)
+            },
+        )?;
+        let mut random = Self::with_state(state);
+        outputs[1..].iter().for_each(|_| {
+            random.next(bits);
+        });
+        Ok(random)
+    }
+}
+
+/// MSVC `rand`: a 32-bit LCG whose outputs are bits 16 to 30 of the state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsvcRand {
+    lcg: Lcg,
+}
+
+impl MsvcRand {
+    /// `srand(seed)`.
+    /// ```
+    /// let mut rand = cryptopals::prng::MsvcRand::new(1);
+    /// assert_eq!((rand.rand(), rand.rand()), (41, 18467));
+    /// ```
+    pub fn new(seed: u32) -> Self {
+        MsvcRand {
+            lcg: Lcg {
+                multiplier: 214_013,
+                increment: 2_531_011,
+                bits: 32,
+                state: seed as u64,
+            },
+        }
+    }
+
+    /// `rand()`, a number in [0, 0x7fff].
+    pub fn rand(&mut self) -> u32 {
+        ((self.lcg.step() >> 16) & 0x7fff) as u32
+    }
+
+    /// Recovers the generator from three or more consecutive outputs.
+    /// The top bit of the state never affects the outputs,
+    /// so the state is solved for modulo 2^31, where the outputs are its top bits.
+    pub fn from_outputs(outputs: &[u32]) -> Result<Self> {
+        if outputs.len() < 3 {
+            return Err(Error::Cryptanalysis(
+                "need at least three outputs".to_owned(),
+            ));
+        }
+        let lcg = Lcg {
+            bits: 31,
+            ..Self::new(0).lcg
+        };
+        let high = |output: u32| (output as u64) << 16;
+        let candidates = lcg.candidates(high(outputs[0]), high(outputs[1]), 16);
+        let state = single(candidates, |state| {
+            let mut rand = Self::new(*state as u32);
+            outputs[1..].iter().all(|output| rand.rand() == *output)
+        })?;
+        let mut rand = Self::new(state as u32);
+        outputs[1..].iter().for_each(|_| {
+            rand.rand();
+        });
+        Ok(rand)
+    }
+}
+
+/// glibc `rand`: an additive lagged Fibonacci generator, r[i] = r[i - 3] + r[i - 31],
+/// seeded by an LCG, whose outputs drop the lowest bit of r[i].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlibcRand {
+    /// The last 31 words, the oldest at index.
+    state: [u32; 31],
+    index: usize,
+}
+
+impl GlibcRand {
+    /// `srand(seed)`.
+    /// ```
+    /// let mut rand = cryptopals::prng::GlibcRand::new(1);
+    /// assert_eq!(rand.rand(), 1_804_289_383);
+    /// ```
+    pub fn new(seed: u32) -> Self {
+        let mut r = vec![if seed == 0 { 1 } else { seed as i32 }];
+        for i in 1..31 {
+            let word = 16807 * (r[i - 1] % 127_773) - 2836 * (r[i - 1] / 127_773);
+            r.push(if word < 0 { word + 2_147_483_647 } else { word });
+        }
+        let mut r: Vec<u32> = r.into_iter().map(|word| word as u32).collect();
+        (31..34).for_each(|i| r.push(r[i - 31]));
+        // the first 310 outputs are thrown away
+        (34..344).for_each(|i| r.push(r[i - 31].wrapping_add(r[i - 3])));
+        Self::with_words(&r[r.len() - 31..])
+    }
+
+    fn with_words(words: &[u32]) -> Self {
+        let mut state = [0; 31];
+        state.copy_from_slice(words);
+        GlibcRand { state, index: 0 }
+    }
+
+    /// `rand()`, a number in [0, 2^31).
+    pub fn rand(&mut self) -> u32 {
+        let word = self.state[self.index].wrapping_add(self.state[(self.index + 28) % 31]);
+        self.state[self.index] = word;
+        self.index = (self.index + 1) % 31;
+        word >> 1
+    }
+
+    /// Recovers the generator from consecutive outputs, a few hundred of them.
+    /// Outputs are exactly the sum of the outputs 3 and 31 before, or one more
+    /// when both of the dropped bits were 1. Those carries give linear equations in the
+    /// dropped bits of the first 31 words, which determine all the dropped bits after them.
+    pub fn from_outputs(outputs: &[u32]) -> Result<Self> {
+        if outputs.len() < 62 {
+            return Err(Error::Cryptanalysis("need at least 62 outputs".to_owned()));
+        }
+        // the dropped bit of every word, as a sum of those of the first 31
+        let mut dropped: Vec<u128> = (0..31).map(|i| 1 << i).collect();
+        let mut equations = vec![];
+        for i in 31..outputs.len() {
+            dropped.push(dropped[i - 31] ^ dropped[i - 3]);
+            let sum = outputs[i - 31].wrapping_add(outputs[i - 3]);
+            match outputs[i].wrapping_sub(sum) & 0x7fff_ffff {
+                0 => {}
+                1 => {
+                    equations.push((dropped[i - 31], true));
+                    equations.push((dropped[i - 3], true));
+                }
+                _ => {
+                    return Err(Error::Cryptanalysis(
+                        "outputs are not consecutive glibc rand outputs".to_owned(),
+                    ))
+                }
+            }
+        }
+        let bits = solve_gf2(&equations, 31).ok_or_else(|| {
+            Error::Cryptanalysis("too few carries to solve the dropped bits".to_owned())
+        })?;
+        let words: Vec<u32> = (outputs.len() - 31..outputs.len())
+            .map(|i| outputs[i] << 1 | ((dropped[i] & bits).count_ones() % 2))
+            .collect();
+        Ok(Self::with_words(&words))
+    }
+}
+
+/// xorshift128+, as V8 implements `Math.random` with it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Xorshift128Plus {
+    state0: u64,
+    state1: u64,
+}
+
+impl Xorshift128Plus {
+    /// The state must not be all zeros.
+    pub fn new(state0: u64, state1: u64) -> Self {
+        assert!(state0 | state1 != 0, "the state must not be all zeros");
+        Xorshift128Plus { state0, state1 }
+    }
+
+    fn step(&mut self) {
+        let mut s1 = self.state0;
+        let s0 = self.state1;
+        self.state0 = s0;
+        s1 ^= s1 << 23;
+        s1 ^= s1 >> 17;
+        s1 ^= s0;
+        s1 ^= s0 >> 26;
+        self.state1 = s1;
+    }
+
+    /// The xorshift128+ output, the sum of the two words of the state.
+    pub fn next_u64(&mut self) -> u64 {
+        self.step();
+        self.state0.wrapping_add(self.state1)
+    }
+
+    /// V8's `Math.random`, made from the top 52 bits of the first word of the state.
+    pub fn next_f64(&mut self) -> f64 {
+        self.step();
+        f64::from_bits(self.state0 >> 12 | 0x3ff0_0000_0000_0000) - 1.0
+    }
+
+    /// Recovers the generator from the top bits of the first word of the state,
+    /// after each of consecutive steps, e.g. 52 bits for `Math.random` outputs.
+    /// 128 bits of outputs or a few more are needed.
+    pub fn from_outputs(outputs: &[u64], bits: u32) -> Result<Self> {
+        if bits == 0 || bits > 64 {
+            return Err(Error::Cryptanalysis(
+                "outputs should have 1 to 64 bits".to_owned(),
+            ));
+        }
+        // every bit of the state as a sum of the bits of the initial state,
+        // the first word's bits being variables 0 to 63
+        let mut state0: Vec<u128> = (0..64).map(|i| 1 << i).collect();
+        let mut state1: Vec<u128> = (64..128).map(|i| 1 << i).collect();
+        let mut equations = vec![];
+        for output in outputs {
+            let mut s1 = state0;
+            let s0 = state1.clone();
+            s1 = xor(&s1, &shift_left(&s1, 23));
+            s1 = xor(&s1, &shift_right(&s1, 17));
+            s1 = xor(&s1, &s0);
+            s1 = xor(&s1, &shift_right(&s0, 26));
+            state0 = s0;
+            state1 = s1;
+            (0..bits).for_each(|i| {
+                let bit = 64 - bits + i;
+                equations.push((state0[bit as usize], output >> i & 1 == 1));
+            });
+        }
+        let initial = solve_gf2(&equations, 128)
+            .ok_or_else(|| Error::Cryptanalysis("outputs do not determine the state".to_owned()))?;
+        if initial == 0 {
+            return Err(Error::Cryptanalysis(
+                "outputs solve to the all zeros state".to_owned(),
+            ));
+        }
+        let mut generator = Self::new(initial as u64, (initial >> 64) as u64);
+        outputs.iter().for_each(|_| generator.step());
+        Ok(generator)
+    }
+
+    /// Recovers the generator from consecutive `Math.random` outputs, usually 3 are enough.
+    /// V8 hands out the numbers of a batch of 64 in reverse, so outputs taken
+    /// from a browser should be reversed into the order they were generated in.
+    pub fn from_v8_doubles(outputs: &[f64]) -> Result<Self> {
+        let mantissas: Vec<u64> = outputs
+            .iter()
+            .map(|output| (output + 1.0).to_bits() & ((1 << 52) - 1))
+            .collect();
+        Self::from_outputs(&mantissas, 52)
+    }
+}
+
+fn xor(a: &[u128], b: &[u128]) -> Vec<u128> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// Bit i of word << shift is bit i - shift of the word.
+fn shift_left(word: &[u128], shift: usize) -> Vec<u128> {
+    (0..64)
+        .map(|i| if i >= shift { word[i - shift] } else { 0 })
+        .collect()
+}
+
+fn shift_right(word: &[u128], shift: usize) -> Vec<u128> {
+    (0..64)
+        .map(|i| if i + shift < 64 { word[i + shift] } else { 0 })
+        .collect()
+}
+
+/// PCG32 (XSH RR): a 64-bit LCG whose outputs are a permutation of its top bits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pcg32 {
+    lcg: Lcg,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+    /// `pcg32_srandom(seed, stream)`.
+    /// ```
+    /// let mut pcg = cryptopals::prng::Pcg32::new(42, 54);
+    /// assert_eq!(pcg.next_u32(), 0xa15c_02b7);
+    /// ```
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut pcg = Self::with_state(0, stream);
+        pcg.lcg.step();
+        pcg.lcg.state = pcg.lcg.state.wrapping_add(seed);
+        pcg.lcg.step();
+        pcg
+    }
+
+    fn with_state(state: u64, stream: u64) -> Self {
+        Pcg32 {
+            lcg: Lcg {
+                multiplier: Self::MULTIPLIER,
+                increment: stream << 1 | 1,
+                bits: 64,
+                state,
+            },
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.lcg.state;
+        self.lcg.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// Recovers the generator of a known stream from three or more consecutive outputs.
+    /// Guessing the rotation of an output reveals the top 37 bits of its state,
+    /// so for every guess of the rotations of the first two outputs,
+    /// the 27 low bits are solved for.
+    pub fn from_outputs(outputs: &[u32], stream: u64) -> Result<Self> {
+        if outputs.len() < 3 {
+            return Err(Error::Cryptanalysis(
+                "need at least three outputs".to_owned(),
+            ));
+        }
+        let lcg = Self::with_state(0, stream).lcg;
+        let candidates = (0..32 * 32)
+            .flat_map(|rotations| {
+                let high = Self::high_bits(outputs[0], rotations % 32);
+                let next_high = Self::high_bits(outputs[1], rotations / 32);
+                lcg.candidates(high, next_high, 27)
+            })
+            .collect();
+        let state = single(candidates, |state| {
+            let mut pcg = Self::with_state(*state, stream);
+            outputs.iter().all(|output| pcg.next_u32() == *output)
+        })?;
+        let mut pcg = Self::with_state(state, stream);
+        outputs.iter().for_each(|_| {
+            pcg.next_u32();
+        });
+        Ok(pcg)
+    }
+
+    /// Bits 27 to 63 of the state which gave the output, if it was rotated by rotation.
+    fn high_bits(output: u32, rotation: u32) -> u64 {
+        // the output is bits 27 to 58 of state ^ (state >> 18)
+        let xorshifted = (output.rotate_left(rotation) as u64) << 27;
+        let mut state = (rotation as u64) << 59;
+        for bit in (27..59).rev() {
+            let above = if bit + 18 < 64 {
+                state >> (bit + 18) & 1
+            } else {
+                0
+            };
+            state |= ((xorshifted >> bit & 1) ^ above) << bit;
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    #[test]
+    #[should_panic]
+    fn java_next_should_panic_for_more_than_32_bits() {
+        JavaRandom::new(1).next(49);
+    }
+
+    #[test]
+    #[should_panic]
+    fn java_next_int_bounded_should_panic_for_a_non_positive_bound() {
+        JavaRandom::new(1).next_int_bounded(0);
+    }
+
+    #[test]
+    fn min_in_range_should_agree_with_brute_force() {
+        let mut random = Random::with_seed(42);
+        for _ in 0..200 {
+            let m = random.range(2, 300) as u128;
+            let a = random.range(1, 300) as u128;
+            let low = random.range(0, m as usize) as u128;
+            let high = random.range(low as usize, m as usize) as u128;
+            let expected = (0..m).find(|x| (low..=high).contains(&(a * x % m)));
+            assert_eq!(
+                min_in_range(a, m, low, high),
+                expected,
+                "{} {} {} {}",
+                a,
+                m,
+                low,
+                high
+            );
+        }
+    }
+
+    #[test]
+    fn generators_should_match_reference_outputs() {
+        assert_eq!(JavaRandom::new(0).next_long(), -4_962_768_465_676_381_896);
+        assert_eq!(JavaRandom::new(42).next_int_bounded(10), 0);
+
+        let mut rand = GlibcRand::new(1);
+        let outputs: Vec<u32> = (0..3).map(|_| rand.rand()).collect();
+        assert_eq!(outputs, [1_804_289_383, 846_930_886, 1_681_692_777]);
+
+        let mut pcg = Pcg32::new(42, 54);
+        let outputs: Vec<u32> = (0..3).map(|_| pcg.next_u32()).collect();
+        assert_eq!(outputs, [0xa15c_02b7, 0x7b47_f409, 0xba1d_3330]);
+    }
+
+    #[test]
+    fn java_random_should_be_recovered_from_truncated_outputs() {
+        for bits in [32, 20] {
+            let mut random = JavaRandom::new(Random::with_seed(bits as u64).next_u64() as i64);
+            let outputs: Vec<u32> = (0..4).map(|_| random.next(bits)).collect();
+            let mut clone = JavaRandom::from_outputs(&outputs, bits).unwrap();
+            assert!((0..100).all(|_| clone.next_int() == random.next_int()));
+        }
+    }
+
+    #[test]
+    fn msvc_rand_should_be_recovered_from_its_outputs() {
+        let mut rand = MsvcRand::new(Random::with_seed(3).next_u64() as u32);
+        let outputs: Vec<u32> = (0..4).map(|_| rand.rand()).collect();
+        let mut clone = MsvcRand::from_outputs(&outputs).unwrap();
+        assert!((0..100).all(|_| clone.rand() == rand.rand()));
+    }
+
+    #[test]
+    fn glibc_rand_should_be_recovered_from_its_outputs() {
+        let mut rand = GlibcRand::new(Random::with_seed(4).next_u64() as u32);
+        let outputs: Vec<u32> = (0..400).map(|_| rand.rand()).collect();
+        let mut clone = GlibcRand::from_outputs(&outputs).unwrap();
+        assert!((0..1000).all(|_| clone.rand() == rand.rand()));
+        assert!(GlibcRand::from_outputs(&[1; 100]).is_err());
+    }
+
+    #[test]
+    fn xorshift128plus_should_be_recovered_from_math_random_outputs() {
+        let mut random = Random::with_seed(8);
+        let mut generator = Xorshift128Plus::new(random.next_u64(), random.next_u64());
+        let outputs: Vec<f64> = (0..4).map(|_| generator.next_f64()).collect();
+        let mut clone = Xorshift128Plus::from_v8_doubles(&outputs).unwrap();
+        assert!((0..100).all(|_| clone.next_f64() == generator.next_f64()));
+        assert_eq!(clone.next_u64(), generator.next_u64());
+        assert!(Xorshift128Plus::from_v8_doubles(&[0.0; 4]).is_err());
+    }
+
+    #[test]
+    fn pcg32_should_be_recovered_from_its_outputs() {
+        let mut random = Random::with_seed(32);
+        let mut pcg = Pcg32::new(random.next_u64(), 54);
+        let outputs: Vec<u32> = (0..3).map(|_| pcg.next_u32()).collect();
+        let mut clone = Pcg32::from_outputs(&outputs, 54).unwrap();
+        assert!((0..100).all(|_| clone.next_u32() == pcg.next_u32()));
+    }
+}
@@ -0,0 +1,84 @@
+//! A small, fast, non-cryptographic random number generator (xorshift64*),
+//! for generating the random keys, IVs and paddings of the challenge oracles.
+//!
+//! It is NOT suitable for generating real keys.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// A generator with a fixed seed, producing the same numbers every time.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut random = Random {
+            // the state must never be 0
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        };
+        if random.state == 0 {
+            random.state = 1;
+        }
+        // scramble seeds which differ in a few bits only
+        (0..4).for_each(|_| {
+            random.next_u64();
+        });
+        random
+    }
+
+    /// A generator seeded from the clock, and a counter,
+    /// so that generators created in quick succession still differ.
+    pub fn from_time() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self::with_seed(nanos ^ count.wrapping_mul(0xbf58_476d_1ce4_e5b9))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn next_u128(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    /// A number in the range [low, high).
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        assert!(low < high, "empty range {}..{}", low, high);
+        low + (self.next_u64() % (high - low) as u64) as usize
+    }
+
+    pub fn bytes(&mut self, length: usize) -> Vec<u8> {
+        (0..length).map(|_| self.next_u64() as u8).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_seed_should_be_reproducible() {
+        let a = Random::with_seed(7).bytes(32);
+        assert_eq!(a, Random::with_seed(7).bytes(32));
+        assert_ne!(a, Random::with_seed(8).bytes(32));
+    }
+
+    #[test]
+    fn range_should_stay_within_bounds() {
+        let mut random = Random::with_seed(1);
+        assert!((0..1000).all(|_| (5..10).contains(&random.range(5, 10))));
+    }
+}
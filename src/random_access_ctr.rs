@@ -0,0 +1,114 @@
+//! Breaking random access read/write CTR mode encryption (cryptopals challenge 25).
+//!
+//! A seekable encrypted disk lets its user rewrite the plaintext at any offset,
+//! re-encrypting it with the keystream at that offset. Whoever can write, and read
+//! back the ciphertext, learns the keystream: writing the ciphertext itself in as
+//! the new plaintext gives back the old plaintext, at any offset, aligned or not.
+
+use crate::aes128::modes;
+use crate::error::{Error, Result};
+use crate::oracle::{Counting, EditOracle};
+use crate::random::Random;
+
+/// Encrypts data under a fixed random key and nonce, and lets the data be edited.
+pub struct EncryptedDisk {
+    key: u128,
+    nonce: u64,
+}
+
+impl EncryptedDisk {
+    pub fn new(random: &mut Random) -> Self {
+        EncryptedDisk {
+            key: random.next_u128(),
+            nonce: random.next_u64(),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        modes::ctr(plaintext, self.key, self.nonce)
+    }
+}
+
+impl EditOracle for EncryptedDisk {
+    fn edit(&self, ciphertext: &[u8], offset: usize, plaintext: &[u8]) -> Result<Vec<u8>> {
+        modes::ctr_edit(ciphertext, self.key, self.nonce, offset, plaintext)
+    }
+}
+
+/// The plaintext of a ciphertext, recovered with the edit oracle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recovery {
+    pub plaintext: Vec<u8>,
+    /// Number of edits made.
+    pub queries: u64,
+}
+
+/// Recovers the plaintext of length bytes of the ciphertext at offset, with one edit.
+pub fn recover_range<O: EditOracle>(
+    oracle: &O,
+    ciphertext: &[u8],
+    offset: usize,
+    length: usize,
+) -> Result<Vec<u8>> {
+    let range = offset..offset + length;
+    let encrypted = ciphertext.get(range.clone()).ok_or(Error::LengthMismatch {
+        expected: ciphertext.len(),
+        actual: offset + length,
+    })?;
+    let edited = oracle.edit(ciphertext, offset, encrypted)?;
+    edited.get(range).map(|p| p.to_vec()).ok_or_else(|| {
+        Error::Cryptanalysis("the edit oracle returned a shorter ciphertext".to_owned())
+    })
+}
+
+/// Recovers the whole plaintext of the ciphertext, editing at most chunk bytes at a time.
+pub fn recover_plaintext<O: EditOracle>(
+    oracle: &O,
+    ciphertext: &[u8],
+    chunk: usize,
+) -> Result<Recovery> {
+    if chunk == 0 {
+        return Err(Error::Cryptanalysis(
+            "chunks should not be empty".to_owned(),
+        ));
+    }
+    let oracle = Counting::new(oracle);
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    for offset in (0..ciphertext.len()).step_by(chunk) {
+        let length = chunk.min(ciphertext.len() - offset);
+        plaintext.extend(recover_range(&oracle, ciphertext, offset, length)?);
+    }
+    Ok(Recovery {
+        plaintext,
+        queries: oracle.queries(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &[u8] = b"I'm back and I'm ringin' the bell\nA rockin' on the mike";
+
+    #[test]
+    fn recover_plaintext_should_work_with_unaligned_chunks() {
+        let disk = EncryptedDisk::new(&mut Random::with_seed(25));
+        let ciphertext = disk.encrypt(PLAINTEXT);
+        for chunk in [1, 7, 16, 100] {
+            let recovery = recover_plaintext(&disk, &ciphertext, chunk).unwrap();
+            assert_eq!(recovery.plaintext, PLAINTEXT.to_vec());
+            assert_eq!(recovery.queries, PLAINTEXT.len().div_ceil(chunk) as u64);
+        }
+    }
+
+    #[test]
+    fn recover_range_should_read_any_range() {
+        let disk = EncryptedDisk::new(&mut Random::with_seed(5));
+        let ciphertext = disk.encrypt(PLAINTEXT);
+        assert_eq!(
+            recover_range(&disk, &ciphertext, 13, 10),
+            Ok(PLAINTEXT[13..23].to_vec())
+        );
+        assert!(recover_range(&disk, &ciphertext, 50, 10).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+/// A Scorer rates how plausible a candidate plaintext is.
+/// Higher scores mean more plausible candidates.
+/// Scorers operate on raw bytes, so that non-text plaintexts
+/// (binaries, compressed data, Latin-1 text) can be ranked as well.
+pub trait Scorer {
+    fn score(&self, bytes: &[u8]) -> f32;
+}
+
+/// Scores bytes as English text,
+/// as the weighted sum of letter frequencies of english alphabet.
+/// Letters [a-zA-Z] and space [' '] are included in the calculated frequency score,
+/// every other byte contributes nothing.
+pub struct LetterFrequency {
+    data: HashMap<u8, f32>,
+}
+
+impl LetterFrequency {
+    pub fn new() -> Self {
+        // source : https://en.wikipedia.org/wiki/Letter_frequency
+        LetterFrequency {
+            data: vec![
+                (b'a', 8.497),
+                (b'b', 1.492),
+                (b'c', 2.202),
+                (b'd', 4.253),
+                (b'e', 11.162),
+                (b'f', 2.228),
+                (b'g', 2.015),
+                (b'h', 6.094),
+                (b'i', 7.546),
+                (b'j', 0.153),
+                (b'k', 1.292),
+                (b'l', 4.025),
+                (b'm', 2.406),
+                (b'n', 6.749),
+                (b'o', 7.507),
+                (b'p', 1.929),
+                (b'q', 0.095),
+                (b'r', 7.587),
+                (b's', 6.327),
+                (b't', 9.356),
+                (b'u', 2.758),
+                (b'v', 0.978),
+                (b'w', 2.560),
+                (b'x', 0.150),
+                (b'y', 1.994),
+                (b'z', 0.077),
+                (b' ', 12.),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+impl Default for LetterFrequency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scorer for LetterFrequency {
+    fn score(&self, bytes: &[u8]) -> f32 {
+        bytes
+            .iter()
+            .map(|b| {
+                self.data
+                    .get(&b.to_ascii_lowercase())
+                    .copied()
+                    .unwrap_or(0.)
+            })
+            .sum()
+    }
+}
+
+/// Scores bytes against an expected byte histogram,
+/// as the log-likelihood of the bytes under that distribution.
+/// The histogram is typically learnt from a sample of the expected kind of plaintext,
+/// e.g. an executable, a compressed archive or text in a particular encoding.
+/// ```
+/// use cryptopals::scoring::{ByteHistogram, Scorer};
+/// let model = ByteHistogram::from_sample(&[0, 0, 0, 0, 1, 1, 2]);
+/// assert!(model.score(&[0, 0, 1]) > model.score(&[7, 8, 9]));
+/// ```
+pub struct ByteHistogram {
+    log_probabilities: Vec<f32>,
+}
+
+impl ByteHistogram {
+    /// Builds the model from the count of every byte in the sample.
+    /// Laplace smoothing is applied, so that no byte is ever impossible.
+    pub fn from_sample(sample: &[u8]) -> Self {
+        let mut counts = [1f32; 256];
+        sample.iter().for_each(|b| counts[*b as usize] += 1.);
+        Self::from_frequencies(&counts)
+    }
+
+    /// Builds the model from relative frequencies of each of the 256 byte values.
+    /// Frequencies need not be normalized.
+    pub fn from_frequencies(frequencies: &[f32; 256]) -> Self {
+        let total: f32 = frequencies.iter().sum();
+        ByteHistogram {
+            log_probabilities: frequencies
+                .iter()
+                .map(|f| (f.max(f32::MIN_POSITIVE) / total).ln())
+                .collect(),
+        }
+    }
+}
+
+impl Scorer for ByteHistogram {
+    fn score(&self, bytes: &[u8]) -> f32 {
+        bytes
+            .iter()
+            .map(|b| self.log_probabilities[*b as usize])
+            .sum()
+    }
+}
+
+/// Scores bytes by how many leading bytes match the best of a set of known file headers
+/// (magic numbers), falling back to another scorer for the rest of the content.
+/// The header match dominates the fallback score.
+pub struct FileHeader<S: Scorer> {
+    headers: Vec<Vec<u8>>,
+    fallback: S,
+}
+
+impl<S: Scorer> FileHeader<S> {
+    pub fn new(headers: Vec<Vec<u8>>, fallback: S) -> Self {
+        FileHeader { headers, fallback }
+    }
+
+    fn matching_prefix(&self, bytes: &[u8]) -> usize {
+        self.headers
+            .iter()
+            .map(|header| {
+                header
+                    .iter()
+                    .zip(bytes.iter())
+                    .take_while(|(h, b)| h == b)
+                    .count()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<S: Scorer> Scorer for FileHeader<S> {
+    fn score(&self, bytes: &[u8]) -> f32 {
+        let fallback = self.fallback.score(bytes);
+        let weight = 1. + fallback.abs();
+        self.matching_prefix(bytes) as f32 * weight + fallback
+    }
+}
+
+/// Magic numbers of some common file formats, for use with FileHeader.
+pub fn common_file_headers() -> Vec<Vec<u8>> {
+    vec![
+        b"\x7fELF".to_vec(),
+        b"MZ".to_vec(),
+        b"\x89PNG\r\n\x1a\n".to_vec(),
+        b"PK\x03\x04".to_vec(),
+        b"\x1f\x8b\x08".to_vec(),
+        b"%PDF-".to_vec(),
+        b"GIF8".to_vec(),
+        b"\xff\xd8\xff".to_vec(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letter_frequency_should_score_case_insensitively() {
+        let scorer = LetterFrequency::new();
+        assert_eq!(scorer.score(b"Ea"), scorer.score(b"eA"));
+        assert_eq!(scorer.score(b"e a"), 11.162 + 12. + 8.497);
+    }
+
+    #[test]
+    fn letter_frequency_should_score_non_letters_as_zero() {
+        assert_eq!(LetterFrequency::new().score(&[0x00, 0xff, b'!']), 0.);
+    }
+
+    #[test]
+    fn byte_histogram_should_prefer_bytes_seen_in_sample() {
+        let model = ByteHistogram::from_sample(&[0x00; 100]);
+        assert!(model.score(&[0x00]) > model.score(&[0x01]));
+        assert_eq!(model.score(&[0x01]), model.score(&[0xff]));
+    }
+
+    #[test]
+    fn file_header_should_prefer_matching_headers() {
+        let scorer = FileHeader::new(common_file_headers(), LetterFrequency::new());
+        assert!(scorer.score(b"\x7fELF\x02\x01") > scorer.score(b"the elf"));
+    }
+}
@@ -0,0 +1,227 @@
+//! SHA-1 (FIPS 180-4), with its chaining state exposed.
+//!
+//! SHA-1 is a Merkle–Damgård hash: the message, padded with 0x80, zeros and its
+//! length in bits, is compressed a 64 byte block at a time into a five word state,
+//! and the digest is the final state. A digest is thus a state to continue hashing from,
+//! which is what makes length extension possible.
+
+use crate::error::Result;
+use crate::hash::Hash;
+use crate::merkle_damgard::{self, Blocks, Endianness, MerkleDamgard};
+use std::convert::TryInto;
+
+pub const BLOCK_SIZE: usize = 64;
+pub const DIGEST_SIZE: usize = 20;
+
+const INITIAL_STATE: [u32; 5] = [
+    0x6745_2301,
+    0xefcd_ab89,
+    0x98ba_dcfe,
+    0x1032_5476,
+    0xc3d2_e1f0,
+];
+
+/// A SHA-1 computation in progress, fed with update.
+/// ```
+/// use cryptopals::{encodings, sha1::Sha1};
+/// let mut sha1 = Sha1::new();
+/// sha1.update(b"a");
+/// sha1.update(b"bc");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&sha1.finalize()),
+///     "a9993e364706816aba3e25717850c26c9cd0d89d");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha1 {
+    state: [u32; 5],
+    blocks: Blocks,
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha1 {
+    pub fn new() -> Self {
+        Sha1 {
+            state: INITIAL_STATE,
+            blocks: Blocks::new(BLOCK_SIZE, 0),
+        }
+    }
+
+    /// Continues a computation from its state after length bytes,
+    /// checked with `merkle_damgard::check_whole_blocks`.
+    pub fn from_state(state: [u32; 5], length: u64) -> Result<Self> {
+        merkle_damgard::check_whole_blocks(length, BLOCK_SIZE)?;
+        Ok(Sha1 {
+            state,
+            blocks: Blocks::new(BLOCK_SIZE, length),
+        })
+    }
+
+    /// Continues a computation from a digest, the state after hashing
+    /// length bytes, message and padding included.
+    pub fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Self::from_state(state_from_digest(digest)?, length)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress(state, block));
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u32; 5] {
+        self.state
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.blocks.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = padding(self.length());
+        self.update(&padding);
+        self.state
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+}
+
+impl Hash for Sha1 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const DIGEST_SIZE: usize = DIGEST_SIZE;
+
+    fn new() -> Self {
+        Sha1::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha1::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha1::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Sha1 {
+    fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Sha1::from_digest(digest, length)
+    }
+
+    fn padding(length: u64) -> Vec<u8> {
+        padding(length)
+    }
+}
+
+/// The SHA-1 digest of the data.
+pub fn sha1(data: &[u8]) -> Vec<u8> {
+    let mut sha1 = Sha1::new();
+    sha1.update(data);
+    sha1.finalize()
+}
+
+/// The padding which follows a message of length bytes:
+/// 0x80, zeros up to 8 bytes short of a block, and the length in bits, big-endian.
+/// ```
+/// let padding = cryptopals::sha1::padding(60);
+/// assert_eq!(padding.len(), 68);
+/// assert_eq!(padding[padding.len() - 2..], [0x01, 0xe0]);
+/// ```
+pub fn padding(length: u64) -> Vec<u8> {
+    merkle_damgard::padding(length, BLOCK_SIZE, Endianness::Big)
+}
+
+/// The chaining state a digest was read from.
+pub fn state_from_digest(digest: &[u8]) -> Result<[u32; 5]> {
+    merkle_damgard::check_digest_length(digest, DIGEST_SIZE)?;
+    let mut state = [0; 5];
+    state
+        .iter_mut()
+        .zip(digest.chunks_exact(4))
+        .for_each(|(word, bytes)| *word = u32::from_be_bytes(bytes.try_into().unwrap()));
+    Ok(state)
+}
+
+fn compress(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    block
+        .chunks_exact(4)
+        .enumerate()
+        .for_each(|(i, bytes)| w[i] = u32::from_be_bytes(bytes.try_into().unwrap()));
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, word) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+            20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+            _ => (b ^ c ^ d, 0xca62_c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+    state
+        .iter_mut()
+        .zip([a, b, c, d, e])
+        .for_each(|(word, value)| *word = word.wrapping_add(value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+
+    #[test]
+    fn sha1_should_match_fips_180_vectors() {
+        let vectors: [(&[u8], &str); 3] = [
+            (b"", "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+            (b"abc", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+            ),
+        ];
+        for (message, digest) in vectors {
+            assert_eq!(encodings::bytes_to_hex(&sha1(message)), digest);
+        }
+        assert_eq!(
+            encodings::bytes_to_hex(&sha1(&vec![b'a'; 1_000_000])),
+            "34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+        );
+    }
+
+    #[test]
+    fn update_should_not_depend_on_how_the_data_is_split() {
+        let data: Vec<u8> = (0..=255).cycle().take(300).collect();
+        for split in [1, 7, 63, 64, 65, 200] {
+            let mut sha1 = Sha1::new();
+            data.chunks(split).for_each(|chunk| sha1.update(chunk));
+            assert_eq!(sha1.finalize(), super::sha1(&data));
+        }
+    }
+
+    #[test]
+    fn from_state_should_need_whole_blocks() {
+        assert!(Sha1::from_state(INITIAL_STATE, 10).is_err());
+        assert!(Sha1::from_state(INITIAL_STATE, 128).is_ok());
+    }
+}
@@ -0,0 +1,762 @@
+//! The SHA-2 family (FIPS 180-4): SHA-224, SHA-256, SHA-384 and SHA-512,
+//! with their chaining states exposed.
+//!
+//! SHA-256 compresses 64 byte blocks into eight 32 bit words, SHA-512 128 byte blocks
+//! into eight 64 bit words, with a 16 byte length field in the padding. SHA-224 and SHA-384
+//! are the same computations from other initial states, with the digest truncated:
+//! their digests leave out part of the state, so they resist length extension.
+
+use crate::error::Result;
+use crate::hash::Hash;
+use crate::merkle_damgard::{self, Blocks, Endianness, MerkleDamgard};
+use std::convert::TryInto;
+
+pub const SHA256_BLOCK_SIZE: usize = 64;
+pub const SHA512_BLOCK_SIZE: usize = 128;
+
+const SHA224_INITIAL_STATE: [u32; 8] = [
+    0xc105_9ed8,
+    0x367c_d507,
+    0x3070_dd17,
+    0xf70e_5939,
+    0xffc0_0b31,
+    0x6858_1511,
+    0x64f9_8fa7,
+    0xbefa_4fa4,
+];
+
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+const SHA384_INITIAL_STATE: [u64; 8] = [
+    0xcbbb_9d5d_c105_9ed8,
+    0x629a_292a_367c_d507,
+    0x9159_015a_3070_dd17,
+    0x152f_ecd8_f70e_5939,
+    0x6733_2667_ffc0_0b31,
+    0x8eb4_4a87_6858_1511,
+    0xdb0c_2e0d_64f9_8fa7,
+    0x47b5_481d_befa_4fa4,
+];
+
+const SHA512_INITIAL_STATE: [u64; 8] = [
+    0x6a09_e667_f3bc_c908,
+    0xbb67_ae85_84ca_a73b,
+    0x3c6e_f372_fe94_f82b,
+    0xa54f_f53a_5f1d_36f1,
+    0x510e_527f_ade6_82d1,
+    0x9b05_688c_2b3e_6c1f,
+    0x1f83_d9ab_fb41_bd6b,
+    0x5be0_cd19_137e_2179,
+];
+
+/// The fractional parts of the cube roots of the first 64 primes.
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+/// The fractional parts of the cube roots of the first 80 primes.
+const SHA512_ROUND_CONSTANTS: [u64; 80] = [
+    0x428a_2f98_d728_ae22,
+    0x7137_4491_23ef_65cd,
+    0xb5c0_fbcf_ec4d_3b2f,
+    0xe9b5_dba5_8189_dbbc,
+    0x3956_c25b_f348_b538,
+    0x59f1_11f1_b605_d019,
+    0x923f_82a4_af19_4f9b,
+    0xab1c_5ed5_da6d_8118,
+    0xd807_aa98_a303_0242,
+    0x1283_5b01_4570_6fbe,
+    0x2431_85be_4ee4_b28c,
+    0x550c_7dc3_d5ff_b4e2,
+    0x72be_5d74_f27b_896f,
+    0x80de_b1fe_3b16_96b1,
+    0x9bdc_06a7_25c7_1235,
+    0xc19b_f174_cf69_2694,
+    0xe49b_69c1_9ef1_4ad2,
+    0xefbe_4786_384f_25e3,
+    0x0fc1_9dc6_8b8c_d5b5,
+    0x240c_a1cc_77ac_9c65,
+    0x2de9_2c6f_592b_0275,
+    0x4a74_84aa_6ea6_e483,
+    0x5cb0_a9dc_bd41_fbd4,
+    0x76f9_88da_8311_53b5,
+    0x983e_5152_ee66_dfab,
+    0xa831_c66d_2db4_3210,
+    0xb003_27c8_98fb_213f,
+    0xbf59_7fc7_beef_0ee4,
+    0xc6e0_0bf3_3da8_8fc2,
+    0xd5a7_9147_930a_a725,
+    0x06ca_6351_e003_826f,
+    0x1429_2967_0a0e_6e70,
+    0x27b7_0a85_46d2_2ffc,
+    0x2e1b_2138_5c26_c926,
+    0x4d2c_6dfc_5ac4_2aed,
+    0x5338_0d13_9d95_b3df,
+    0x650a_7354_8baf_63de,
+    0x766a_0abb_3c77_b2a8,
+    0x81c2_c92e_47ed_aee6,
+    0x9272_2c85_1482_353b,
+    0xa2bf_e8a1_4cf1_0364,
+    0xa81a_664b_bc42_3001,
+    0xc24b_8b70_d0f8_9791,
+    0xc76c_51a3_0654_be30,
+    0xd192_e819_d6ef_5218,
+    0xd699_0624_5565_a910,
+    0xf40e_3585_5771_202a,
+    0x106a_a070_32bb_d1b8,
+    0x19a4_c116_b8d2_d0c8,
+    0x1e37_6c08_5141_ab53,
+    0x2748_774c_df8e_eb99,
+    0x34b0_bcb5_e19b_48a8,
+    0x391c_0cb3_c5c9_5a63,
+    0x4ed8_aa4a_e341_8acb,
+    0x5b9c_ca4f_7763_e373,
+    0x682e_6ff3_d6b2_b8a3,
+    0x748f_82ee_5def_b2fc,
+    0x78a5_636f_4317_2f60,
+    0x84c8_7814_a1f0_ab72,
+    0x8cc7_0208_1a64_39ec,
+    0x90be_fffa_2363_1e28,
+    0xa450_6ceb_de82_bde9,
+    0xbef9_a3f7_b2c6_7915,
+    0xc671_78f2_e372_532b,
+    0xca27_3ece_ea26_619c,
+    0xd186_b8c7_21c0_c207,
+    0xeada_7dd6_cde0_eb1e,
+    0xf57d_4f7f_ee6e_d178,
+    0x06f0_67aa_7217_6fba,
+    0x0a63_7dc5_a2c8_98a6,
+    0x113f_9804_bef9_0dae,
+    0x1b71_0b35_131c_471b,
+    0x28db_77f5_2304_7d84,
+    0x32ca_ab7b_40c7_2493,
+    0x3c9e_be0a_15c9_bebc,
+    0x431d_67c4_9c10_0d4c,
+    0x4cc5_d4be_cb3e_42b6,
+    0x597f_299c_fc65_7e2a,
+    0x5fcb_6fab_3ad6_faec,
+    0x6c44_198c_4a47_5817,
+];
+
+/// A SHA-256 computation in progress, fed with update.
+/// ```
+/// use cryptopals::{encodings, sha2::Sha256};
+/// let mut sha256 = Sha256::new();
+/// sha256.update(b"a");
+/// sha256.update(b"bc");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&sha256.finalize()),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha256 {
+    state: [u32; 8],
+    blocks: Blocks,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Sha256 {
+            state: SHA256_INITIAL_STATE,
+            blocks: Blocks::new(SHA256_BLOCK_SIZE, 0),
+        }
+    }
+
+    /// Continues a computation from its state after length bytes,
+    /// checked with `merkle_damgard::check_whole_blocks`.
+    pub fn from_state(state: [u32; 8], length: u64) -> Result<Self> {
+        merkle_damgard::check_whole_blocks(length, SHA256_BLOCK_SIZE)?;
+        Ok(Sha256 {
+            state,
+            blocks: Blocks::new(SHA256_BLOCK_SIZE, length),
+        })
+    }
+
+    /// Continues a computation from a digest, the state after hashing
+    /// length bytes, message and padding included.
+    pub fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        let mut state = [0; 8];
+        merkle_damgard::check_digest_length(digest, 32)?;
+        state
+            .iter_mut()
+            .zip(digest.chunks_exact(4))
+            .for_each(|(word, bytes)| *word = u32::from_be_bytes(bytes.try_into().unwrap()));
+        Self::from_state(state, length)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress_256(state, block));
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u32; 8] {
+        self.state
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.blocks.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = sha256_padding(self.length());
+        self.update(&padding);
+        self.state
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+}
+
+/// A SHA-224 computation in progress: SHA-256 from another initial state,
+/// with the last word of the state left out of the digest.
+#[derive(Clone, Debug)]
+pub struct Sha224(Sha256);
+
+impl Default for Sha224 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha224 {
+    pub fn new() -> Self {
+        Sha224(Sha256 {
+            state: SHA224_INITIAL_STATE,
+            blocks: Blocks::new(SHA256_BLOCK_SIZE, 0),
+        })
+    }
+
+    /// Like `Sha256::from_state`.
+    pub fn from_state(state: [u32; 8], length: u64) -> Result<Self> {
+        Sha256::from_state(state, length).map(Sha224)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data)
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u32; 8] {
+        self.0.state()
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.0.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = self.0.finalize();
+        digest.truncate(28);
+        digest
+    }
+}
+
+/// A SHA-512 computation in progress, fed with update.
+#[derive(Clone, Debug)]
+pub struct Sha512 {
+    state: [u64; 8],
+    blocks: Blocks,
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha512 {
+    pub fn new() -> Self {
+        Sha512 {
+            state: SHA512_INITIAL_STATE,
+            blocks: Blocks::new(SHA512_BLOCK_SIZE, 0),
+        }
+    }
+
+    /// Continues a computation from its state after length bytes,
+    /// checked with `merkle_damgard::check_whole_blocks`.
+    pub fn from_state(state: [u64; 8], length: u64) -> Result<Self> {
+        merkle_damgard::check_whole_blocks(length, SHA512_BLOCK_SIZE)?;
+        Ok(Sha512 {
+            state,
+            blocks: Blocks::new(SHA512_BLOCK_SIZE, length),
+        })
+    }
+
+    /// Continues a computation from a digest, the state after hashing
+    /// length bytes, message and padding included.
+    pub fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        let mut state = [0; 8];
+        merkle_damgard::check_digest_length(digest, 64)?;
+        state
+            .iter_mut()
+            .zip(digest.chunks_exact(8))
+            .for_each(|(word, bytes)| *word = u64::from_be_bytes(bytes.try_into().unwrap()));
+        Self::from_state(state, length)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress_512(state, block));
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u64; 8] {
+        self.state
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.blocks.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = sha512_padding(self.length());
+        self.update(&padding);
+        self.state
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+}
+
+/// A SHA-384 computation in progress: SHA-512 from another initial state,
+/// with the last two words of the state left out of the digest.
+#[derive(Clone, Debug)]
+pub struct Sha384(Sha512);
+
+impl Default for Sha384 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha384 {
+    pub fn new() -> Self {
+        Sha384(Sha512 {
+            state: SHA384_INITIAL_STATE,
+            blocks: Blocks::new(SHA512_BLOCK_SIZE, 0),
+        })
+    }
+
+    /// Like `Sha512::from_state`.
+    pub fn from_state(state: [u64; 8], length: u64) -> Result<Self> {
+        Sha512::from_state(state, length).map(Sha384)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data)
+    }
+
+    /// The chaining state, after the whole blocks hashed so far.
+    pub fn state(&self) -> [u64; 8] {
+        self.0.state()
+    }
+
+    /// Number of bytes hashed so far.
+    pub fn length(&self) -> u64 {
+        self.0.length()
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = self.0.finalize();
+        digest.truncate(48);
+        digest
+    }
+}
+
+impl Hash for Sha224 {
+    const BLOCK_SIZE: usize = SHA256_BLOCK_SIZE;
+    const DIGEST_SIZE: usize = 28;
+
+    fn new() -> Self {
+        Sha224::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha224::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha224::finalize(self)
+    }
+}
+
+impl Hash for Sha256 {
+    const BLOCK_SIZE: usize = SHA256_BLOCK_SIZE;
+    const DIGEST_SIZE: usize = 32;
+
+    fn new() -> Self {
+        Sha256::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha256::finalize(self)
+    }
+}
+
+impl Hash for Sha384 {
+    const BLOCK_SIZE: usize = SHA512_BLOCK_SIZE;
+    const DIGEST_SIZE: usize = 48;
+
+    fn new() -> Self {
+        Sha384::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha384::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha384::finalize(self)
+    }
+}
+
+impl Hash for Sha512 {
+    const BLOCK_SIZE: usize = SHA512_BLOCK_SIZE;
+    const DIGEST_SIZE: usize = 64;
+
+    fn new() -> Self {
+        Sha512::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha512::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha512::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Sha256 {
+    fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Sha256::from_digest(digest, length)
+    }
+
+    fn padding(length: u64) -> Vec<u8> {
+        sha256_padding(length)
+    }
+}
+
+impl MerkleDamgard for Sha512 {
+    fn from_digest(digest: &[u8], length: u64) -> Result<Self> {
+        Sha512::from_digest(digest, length)
+    }
+
+    fn padding(length: u64) -> Vec<u8> {
+        sha512_padding(length)
+    }
+}
+
+/// The SHA-224 digest of the data.
+pub fn sha224(data: &[u8]) -> Vec<u8> {
+    Sha224::digest(data)
+}
+
+/// The SHA-256 digest of the data.
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data)
+}
+
+/// The SHA-384 digest of the data.
+pub fn sha384(data: &[u8]) -> Vec<u8> {
+    Sha384::digest(data)
+}
+
+/// The SHA-512 digest of the data.
+pub fn sha512(data: &[u8]) -> Vec<u8> {
+    Sha512::digest(data)
+}
+
+/// The padding of SHA-224 and SHA-256 which follows a message of length bytes,
+/// ending with the length in bits in 8 bytes, big-endian.
+pub fn sha256_padding(length: u64) -> Vec<u8> {
+    merkle_damgard::padding(length, SHA256_BLOCK_SIZE, Endianness::Big)
+}
+
+/// The padding of SHA-384 and SHA-512 which follows a message of length bytes,
+/// ending with the length in bits in 16 bytes, big-endian.
+/// ```
+/// let padding = cryptopals::sha2::sha512_padding(100);
+/// assert_eq!(padding.len(), 28);
+/// assert_eq!(padding[padding.len() - 3..], [0x00, 0x03, 0x20]);
+/// ```
+pub fn sha512_padding(length: u64) -> Vec<u8> {
+    merkle_damgard::padding(length, SHA512_BLOCK_SIZE, Endianness::Big)
+}
+
+fn compress_256(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    block
+        .chunks_exact(4)
+        .enumerate()
+        .for_each(|(i, bytes)| w[i] = u32::from_be_bytes(bytes.try_into().unwrap()));
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (word, k) in w.iter().zip(SHA256_ROUND_CONSTANTS) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let choice = (e & f) ^ (!e & g);
+        let temp1 = h
+            .wrapping_add(s1)
+            .wrapping_add(choice)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let majority = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(majority);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+    state
+        .iter_mut()
+        .zip([a, b, c, d, e, f, g, h])
+        .for_each(|(word, value)| *word = word.wrapping_add(value));
+}
+
+fn compress_512(state: &mut [u64; 8], block: &[u8]) {
+    let mut w = [0u64; 80];
+    block
+        .chunks_exact(8)
+        .enumerate()
+        .for_each(|(i, bytes)| w[i] = u64::from_be_bytes(bytes.try_into().unwrap()));
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (word, k) in w.iter().zip(SHA512_ROUND_CONSTANTS) {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let choice = (e & f) ^ (!e & g);
+        let temp1 = h
+            .wrapping_add(s1)
+            .wrapping_add(choice)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let majority = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(majority);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+    state
+        .iter_mut()
+        .zip([a, b, c, d, e, f, g, h])
+        .for_each(|(word, value)| *word = word.wrapping_add(value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+    use crate::length_extension::{forge, SecretPrefixMac};
+    use crate::oracle::MacOracle;
+    use crate::random::Random;
+
+    const TWO_BLOCKS: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    const FOUR_BLOCKS: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    #[test]
+    fn sha256_should_match_fips_180_vectors() {
+        let vectors: [(&[u8], &str, &str); 3] = [
+            (
+                b"",
+                "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                TWO_BLOCKS,
+                "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (message, digest224, digest256) in vectors {
+            assert_eq!(encodings::bytes_to_hex(&sha224(message)), digest224);
+            assert_eq!(encodings::bytes_to_hex(&sha256(message)), digest256);
+        }
+    }
+
+    #[test]
+    fn sha512_should_match_fips_180_vectors() {
+        let vectors: [(&[u8], &str, &str); 3] = [
+            (
+                b"",
+                "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+            ),
+            (
+                b"abc",
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            ),
+            (
+                FOUR_BLOCKS,
+                "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039",
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+            ),
+        ];
+        for (message, digest384, digest512) in vectors {
+            assert_eq!(encodings::bytes_to_hex(&sha384(message)), digest384);
+            assert_eq!(encodings::bytes_to_hex(&sha512(message)), digest512);
+        }
+    }
+
+    #[test]
+    fn update_should_not_depend_on_how_the_data_is_split() {
+        let data: Vec<u8> = (0..=255).cycle().take(300).collect();
+        for split in [1, 7, 63, 64, 65, 127, 128, 129] {
+            let mut sha256 = Sha256::new();
+            let mut sha384 = Sha384::new();
+            data.chunks(split).for_each(|chunk| {
+                sha256.update(chunk);
+                sha384.update(chunk);
+            });
+            assert_eq!(sha256.finalize(), super::sha256(&data));
+            assert_eq!(sha384.finalize(), super::sha384(&data));
+        }
+    }
+
+    #[test]
+    fn from_state_should_continue_after_whole_blocks() {
+        let mut sha224 = Sha224::new();
+        sha224.update(&FOUR_BLOCKS[..64]);
+        let mut continued = Sha224::from_state(sha224.state(), sha224.length()).unwrap();
+        continued.update(&FOUR_BLOCKS[64..]);
+        assert_eq!(continued.finalize(), super::sha224(FOUR_BLOCKS));
+        assert!(Sha512::from_state(SHA512_INITIAL_STATE, 64).is_err());
+        assert!(Sha256::from_digest(&super::sha224(b""), 64).is_err());
+    }
+
+    #[test]
+    fn forge_should_extend_sha256_and_sha512_macs() {
+        let mut random = Random::with_seed(47);
+        let message = b"user=alice;role=guest";
+        let service = SecretPrefixMac::<Sha256>::new(1..=32, &mut random);
+        let mac = service.mac(message);
+        let forgery = forge::<Sha256, _>(&service, message, &mac, b";role=admin", 1..=32).unwrap();
+        assert!(service.verify(&forgery.message, &forgery.mac).unwrap());
+
+        let service = SecretPrefixMac::<Sha512>::new(1..=32, &mut random);
+        let mac = service.mac(message);
+        let forgery = forge::<Sha512, _>(&service, message, &mac, b";role=admin", 1..=32).unwrap();
+        assert!(service.verify(&forgery.message, &forgery.mac).unwrap());
+    }
+}
@@ -0,0 +1,632 @@
+//! SHA-3 (FIPS 202): the Keccak-f[1600] permutation, the sponge built on it,
+//! SHA3-224/256/384/512 and the SHAKE128/256 extendable-output functions.
+//!
+//! The sponge absorbs the message a rate-sized block at a time into a 200 byte state
+//! and squeezes the output out of the same rate bytes. The rest of the state, the capacity,
+//! never reaches the output, so unlike a Merkle–Damgård digest, a SHA-3 digest is not
+//! a state to continue hashing from: length extension fails.
+
+use crate::error::{Error, Result};
+use crate::hash::Hash;
+use crate::merkle_damgard::{self, Blocks};
+use std::convert::TryInto;
+
+/// Size of the state in bytes.
+pub const STATE_SIZE: usize = 200;
+
+/// Domain separation bits of SHA-3, appended to the message before the padding.
+pub const SHA3_SUFFIX: u8 = 0x06;
+/// Domain separation bits of SHAKE.
+pub const SHAKE_SUFFIX: u8 = 0x1f;
+/// Domain separation bits of cSHAKE, on which KMAC is built. The message absorbed is
+/// preceded by the function name and customization string, encoded and padded to a block.
+pub const CSHAKE_SUFFIX: u8 = 0x04;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation of each lane, indexed by x + 5y.
+const ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The Keccak-f[1600] permutation of a state of 5×5 lanes, indexed by x + 5y.
+pub fn keccak_f(state: &mut [u64; 25]) {
+    for round_constant in ROUND_CONSTANTS {
+        // θ: each lane is xored with the parities of two neighbouring columns
+        let mut parities = [0u64; 5];
+        for (i, lane) in state.iter().enumerate() {
+            parities[i % 5] ^= lane;
+        }
+        for (i, lane) in state.iter_mut().enumerate() {
+            *lane ^= parities[(i + 4) % 5] ^ parities[(i + 1) % 5].rotate_left(1);
+        }
+        // ρ and π: lanes are rotated, and moved from (x, y) to (y, 2x + 3y)
+        let mut moved = [0u64; 25];
+        for (i, lane) in state.iter().enumerate() {
+            let (x, y) = (i % 5, i / 5);
+            moved[y + 5 * ((2 * x + 3 * y) % 5)] = lane.rotate_left(ROTATIONS[i]);
+        }
+        // χ: the only non-linear step, along rows
+        for (i, lane) in state.iter_mut().enumerate() {
+            let row = i - i % 5;
+            *lane = moved[i] ^ (!moved[row + (i + 1) % 5] & moved[row + (i + 2) % 5]);
+        }
+        // ι
+        state[0] ^= round_constant;
+    }
+}
+
+/// A Keccak sponge absorbing data, fed with absorb.
+/// ```
+/// use cryptopals::{encodings, sha3::{Sponge, SHA3_SUFFIX}};
+/// let mut sponge = Sponge::new(136, SHA3_SUFFIX).unwrap();
+/// sponge.absorb(b"abc");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&sponge.finish().squeeze(32)),
+///     "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sponge {
+    state: [u64; 25],
+    suffix: u8,
+    blocks: Blocks,
+}
+
+impl Sponge {
+    /// A sponge of rate bytes, a multiple of 8 below 200,
+    /// whose padding starts with the domain separation bits of suffix.
+    pub fn new(rate: usize, suffix: u8) -> Result<Self> {
+        Self::from_state([0; 25], rate, suffix, 0)
+    }
+
+    /// Continues absorbing from the state after length bytes,
+    /// checked with `merkle_damgard::check_whole_blocks`.
+    pub fn from_state(state: [u64; 25], rate: usize, suffix: u8, length: u64) -> Result<Self> {
+        if !rate.is_multiple_of(8) || rate == 0 || rate >= STATE_SIZE {
+            return Err(Error::LengthMismatch {
+                expected: rate.next_multiple_of(8).clamp(8, STATE_SIZE - 8),
+                actual: rate,
+            });
+        }
+        merkle_damgard::check_whole_blocks(length, rate)?;
+        Ok(Self::with_rate(state, rate, suffix, length))
+    }
+
+    /// A sponge of a rate known to be valid.
+    fn with_rate(state: [u64; 25], rate: usize, suffix: u8, length: u64) -> Self {
+        Sponge {
+            state,
+            suffix,
+            blocks: Blocks::new(rate, length),
+        }
+    }
+
+    pub fn absorb(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| {
+            for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+                *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+            }
+            keccak_f(state);
+        });
+    }
+
+    /// The state, after the whole blocks absorbed so far.
+    pub fn state(&self) -> [u64; 25] {
+        self.state
+    }
+
+    /// Number of bytes absorbed so far.
+    pub fn length(&self) -> u64 {
+        self.blocks.length()
+    }
+
+    pub fn rate(&self) -> usize {
+        self.blocks.block_size()
+    }
+
+    /// Pads the data absorbed, and switches to squeezing.
+    pub fn finish(mut self) -> Squeeze {
+        let rate = self.rate();
+        let padding = padding(self.length(), rate, self.suffix);
+        self.absorb(&padding);
+        Squeeze {
+            state: self.state,
+            rate,
+            offset: 0,
+        }
+    }
+}
+
+/// The padding which follows length bytes absorbed at the given rate:
+/// the domain separation bits, then a one bit, zeros and a final one bit.
+pub fn padding(length: u64, rate: usize, suffix: u8) -> Vec<u8> {
+    let mut padding = vec![0; rate - length as usize % rate];
+    padding[0] = suffix;
+    *padding.last_mut().unwrap() |= 0x80;
+    padding
+}
+
+/// A Keccak sponge squeezing output, any number of bytes at a time.
+#[derive(Clone, Debug)]
+pub struct Squeeze {
+    state: [u64; 25],
+    rate: usize,
+    offset: usize,
+}
+
+impl Squeeze {
+    /// The next length bytes of output.
+    pub fn squeeze(&mut self, length: usize) -> Vec<u8> {
+        let mut output = Vec::with_capacity(length);
+        while output.len() < length {
+            if self.offset == self.rate {
+                keccak_f(&mut self.state);
+                self.offset = 0;
+            }
+            let lane = self.state[self.offset / 8].to_le_bytes();
+            let taken = (8 - self.offset % 8).min(length - output.len());
+            output.extend_from_slice(&lane[self.offset % 8..self.offset % 8 + taken]);
+            self.offset += taken;
+        }
+        output
+    }
+
+    /// The state the output is read from.
+    pub fn state(&self) -> [u64; 25] {
+        self.state
+    }
+}
+
+/// A SHA3-224 computation in progress, fed with update.
+#[derive(Clone, Debug)]
+pub struct Sha3_224(Sponge);
+
+/// A SHA3-256 computation in progress, fed with update.
+/// ```
+/// use cryptopals::{encodings, sha3::Sha3_256};
+/// let mut sha3 = Sha3_256::new();
+/// sha3.update(b"a");
+/// sha3.update(b"bc");
+/// assert_eq!(
+///     encodings::bytes_to_hex(&sha3.finalize()),
+///     "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha3_256(Sponge);
+
+/// A SHA3-384 computation in progress, fed with update.
+#[derive(Clone, Debug)]
+pub struct Sha3_384(Sponge);
+
+/// A SHA3-512 computation in progress, fed with update.
+#[derive(Clone, Debug)]
+pub struct Sha3_512(Sponge);
+
+impl Sha3_224 {
+    pub fn new() -> Self {
+        Sha3_224(Sponge::with_rate([0; 25], 144, SHA3_SUFFIX, 0))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    /// The sponge underneath.
+    pub fn sponge(&self) -> &Sponge {
+        &self.0
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(self) -> Vec<u8> {
+        self.0.finish().squeeze(28)
+    }
+}
+
+impl Sha3_256 {
+    pub fn new() -> Self {
+        Sha3_256(Sponge::with_rate([0; 25], 136, SHA3_SUFFIX, 0))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    /// The sponge underneath.
+    pub fn sponge(&self) -> &Sponge {
+        &self.0
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(self) -> Vec<u8> {
+        self.0.finish().squeeze(32)
+    }
+}
+
+impl Sha3_384 {
+    pub fn new() -> Self {
+        Sha3_384(Sponge::with_rate([0; 25], 104, SHA3_SUFFIX, 0))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    /// The sponge underneath.
+    pub fn sponge(&self) -> &Sponge {
+        &self.0
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(self) -> Vec<u8> {
+        self.0.finish().squeeze(48)
+    }
+}
+
+impl Sha3_512 {
+    pub fn new() -> Self {
+        Sha3_512(Sponge::with_rate([0; 25], 72, SHA3_SUFFIX, 0))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    /// The sponge underneath.
+    pub fn sponge(&self) -> &Sponge {
+        &self.0
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(self) -> Vec<u8> {
+        self.0.finish().squeeze(64)
+    }
+}
+
+impl Default for Sha3_224 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Sha3_256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Sha3_384 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Sha3_512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hash for Sha3_224 {
+    const BLOCK_SIZE: usize = 144;
+    const DIGEST_SIZE: usize = 28;
+
+    fn new() -> Self {
+        Sha3_224::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3_224::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha3_224::finalize(self)
+    }
+}
+
+impl Hash for Sha3_256 {
+    const BLOCK_SIZE: usize = 136;
+    const DIGEST_SIZE: usize = 32;
+
+    fn new() -> Self {
+        Sha3_256::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3_256::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha3_256::finalize(self)
+    }
+}
+
+impl Hash for Sha3_384 {
+    const BLOCK_SIZE: usize = 104;
+    const DIGEST_SIZE: usize = 48;
+
+    fn new() -> Self {
+        Sha3_384::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3_384::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha3_384::finalize(self)
+    }
+}
+
+impl Hash for Sha3_512 {
+    const BLOCK_SIZE: usize = 72;
+    const DIGEST_SIZE: usize = 64;
+
+    fn new() -> Self {
+        Sha3_512::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3_512::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha3_512::finalize(self)
+    }
+}
+
+/// A SHAKE128 computation in progress, fed with update, whose output is squeezed
+/// to any length.
+/// ```
+/// use cryptopals::{encodings, sha3::Shake128};
+/// let mut shake = Shake128::new();
+/// shake.update(b"abc");
+/// let mut output = shake.finalize();
+/// assert_eq!(encodings::bytes_to_hex(&output.squeeze(4)), "5881092d");
+/// assert_eq!(encodings::bytes_to_hex(&output.squeeze(4)), "d818bf5c");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Shake128(Sponge);
+
+/// A SHAKE256 computation in progress, fed with update, whose output is squeezed
+/// to any length.
+#[derive(Clone, Debug)]
+pub struct Shake256(Sponge);
+
+impl Shake128 {
+    pub fn new() -> Self {
+        Shake128(Sponge::with_rate([0; 25], 168, SHAKE_SUFFIX, 0))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    /// Pads the message and returns the sponge to squeeze the output from.
+    pub fn finalize(self) -> Squeeze {
+        self.0.finish()
+    }
+}
+
+impl Shake256 {
+    pub fn new() -> Self {
+        Shake256(Sponge::with_rate([0; 25], 136, SHAKE_SUFFIX, 0))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.absorb(data)
+    }
+
+    /// Pads the message and returns the sponge to squeeze the output from.
+    pub fn finalize(self) -> Squeeze {
+        self.0.finish()
+    }
+}
+
+impl Default for Shake128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Shake256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The SHA3-224 digest of the data.
+pub fn sha3_224(data: &[u8]) -> Vec<u8> {
+    Sha3_224::digest(data)
+}
+
+/// The SHA3-256 digest of the data.
+pub fn sha3_256(data: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(data)
+}
+
+/// The SHA3-384 digest of the data.
+pub fn sha3_384(data: &[u8]) -> Vec<u8> {
+    Sha3_384::digest(data)
+}
+
+/// The SHA3-512 digest of the data.
+pub fn sha3_512(data: &[u8]) -> Vec<u8> {
+    Sha3_512::digest(data)
+}
+
+/// The first length bytes of the SHAKE128 output for the data.
+pub fn shake128(data: &[u8], length: usize) -> Vec<u8> {
+    let mut shake = Shake128::new();
+    shake.update(data);
+    shake.finalize().squeeze(length)
+}
+
+/// The first length bytes of the SHAKE256 output for the data.
+pub fn shake256(data: &[u8], length: usize) -> Vec<u8> {
+    let mut shake = Shake256::new();
+    shake.update(data);
+    shake.finalize().squeeze(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encodings;
+
+    const TWO_BLOCKS: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+    #[test]
+    fn sha3_should_match_nist_vectors() {
+        let vectors: [(&[u8], [&str; 4]); 3] = [
+            (
+                b"",
+                [
+                    "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+                    "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+                    "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+                    "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+                ],
+            ),
+            (
+                b"abc",
+                [
+                    "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+                    "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+                    "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+                    "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+                ],
+            ),
+            (
+                TWO_BLOCKS,
+                [
+                    "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33",
+                    "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+                    "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22",
+                    "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+                ],
+            ),
+        ];
+        for (message, digests) in vectors {
+            assert_eq!(encodings::bytes_to_hex(&sha3_224(message)), digests[0]);
+            assert_eq!(encodings::bytes_to_hex(&sha3_256(message)), digests[1]);
+            assert_eq!(encodings::bytes_to_hex(&sha3_384(message)), digests[2]);
+            assert_eq!(encodings::bytes_to_hex(&sha3_512(message)), digests[3]);
+        }
+        assert_eq!(
+            encodings::bytes_to_hex(&sha3_256(&vec![b'a'; 1_000_000])),
+            "5c8875ae474a3634ba4fd55ec85bffd661f32aca75c6d699d0cdcb6c115891c1"
+        );
+    }
+
+    #[test]
+    fn shake_should_match_nist_vectors() {
+        assert_eq!(
+            encodings::bytes_to_hex(&shake128(b"", 32)),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+        assert_eq!(
+            encodings::bytes_to_hex(&shake256(b"", 64)),
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"
+        );
+        assert_eq!(
+            encodings::bytes_to_hex(&shake256(b"abc", 64)),
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+        );
+        // output squeezed a few bytes at a time, across blocks, is the same
+        let mut shake = Shake128::new();
+        shake.update(b"abc");
+        let mut output = shake.finalize();
+        let squeezed: Vec<u8> = (0..100).flat_map(|_| output.squeeze(7)).collect();
+        assert_eq!(squeezed, shake128(b"abc", 700));
+    }
+
+    #[test]
+    fn update_should_not_depend_on_how_the_data_is_split() {
+        let data: Vec<u8> = (0..=255).cycle().take(400).collect();
+        for split in [1, 7, 71, 72, 73, 136, 137] {
+            let mut sha3 = Sha3_512::new();
+            data.chunks(split).for_each(|chunk| sha3.update(chunk));
+            assert_eq!(sha3.finalize(), sha3_512(&data));
+        }
+    }
+
+    #[test]
+    fn sponge_should_compute_cshake_nist_vector() {
+        // cSHAKE128 sample 1: no function name, customization "Email Signature",
+        // as bytepad(encode_string(N) || encode_string(S), 168)
+        let mut prefix = vec![0x01, 0xa8, 0x01, 0x00, 0x01, 0x78];
+        prefix.extend_from_slice(b"Email Signature");
+        prefix.resize(168, 0);
+        let mut sponge = Sponge::new(168, CSHAKE_SUFFIX).unwrap();
+        sponge.absorb(&prefix);
+        sponge.absorb(&[0, 1, 2, 3]);
+        assert_eq!(
+            encodings::bytes_to_hex(&sponge.finish().squeeze(32)),
+            "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"
+        );
+    }
+
+    #[test]
+    fn sponge_should_reject_invalid_rates_and_lengths() {
+        for rate in [0, 100, 200, 208] {
+            assert!(Sponge::new(rate, SHA3_SUFFIX).is_err());
+        }
+        assert!(Sponge::from_state([0; 25], 136, SHA3_SUFFIX, 100).is_err());
+    }
+
+    #[test]
+    fn length_extension_should_need_the_capacity() {
+        let message = b"secret and message";
+        let extension = b";admin=true";
+        let glued = [
+            &message[..],
+            &padding(message.len() as u64, 136, SHA3_SUFFIX),
+        ]
+        .concat();
+        let expected = sha3_256(&[&glued[..], extension].concat());
+
+        // the digest gives only the first 4 lanes of the state, the other 21 are guessed as 0
+        let digest = sha3_256(message);
+        let mut state = [0; 25];
+        for (lane, bytes) in state.iter_mut().zip(digest.chunks_exact(8)) {
+            *lane = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        let mut sponge = Sponge::from_state(state, 136, SHA3_SUFFIX, glued.len() as u64).unwrap();
+        sponge.absorb(extension);
+        assert_ne!(sponge.finish().squeeze(32), expected);
+
+        // with the whole state, capacity included, hashing goes on
+        let mut sha3 = Sha3_256::new();
+        sha3.update(&glued);
+        let state = sha3.sponge().state();
+        let mut sponge = Sponge::from_state(state, 136, SHA3_SUFFIX, glued.len() as u64).unwrap();
+        sponge.absorb(extension);
+        assert_eq!(sponge.finish().squeeze(32), expected);
+    }
+}